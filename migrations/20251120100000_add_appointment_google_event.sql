@@ -0,0 +1,4 @@
+ALTER TABLE appointments
+ADD COLUMN IF NOT EXISTS google_event_id VARCHAR(1024),
+ADD COLUMN IF NOT EXISTS google_calendar_id VARCHAR(255),
+ADD COLUMN IF NOT EXISTS google_event_etag VARCHAR(255);
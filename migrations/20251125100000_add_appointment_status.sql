@@ -0,0 +1,18 @@
+ALTER TABLE appointments
+ADD COLUMN IF NOT EXISTS status VARCHAR(20) NOT NULL DEFAULT 'pending' CHECK (
+    status IN (
+        'pending',
+        'confirmed',
+        'completed',
+        'no_show',
+        'cancelled'
+    )
+);
+
+UPDATE appointments
+SET
+    status = 'cancelled'
+WHERE
+    cancelled_at IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS appointments_business_status_idx ON appointments (business_id, status);
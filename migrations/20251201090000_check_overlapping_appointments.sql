@@ -0,0 +1,36 @@
+-- Bookings that already overlap would keep the exclusion constraint from
+-- being added. They are real customer bookings, so the migration does not
+-- pick one to cancel, it stops and lists every clashing pair instead. Once
+-- the business has cancelled or moved one booking of each pair, run the
+-- migrations again.
+DO $$
+DECLARE
+    clashes TEXT;
+BEGIN
+    SELECT string_agg(
+        format(
+            'business %s: appointment %s overlaps appointment %s',
+            earlier.business_id,
+            earlier.id,
+            later.id
+        ),
+        E'\n'
+        ORDER BY earlier.business_id, earlier.appointment_start_time, earlier.id, later.id
+    )
+    INTO clashes
+    FROM appointments earlier
+    JOIN appointments later
+        ON later.business_id = earlier.business_id
+        AND later.id > earlier.id
+        AND later.status IN ('pending', 'confirmed')
+        AND earlier.appointment_start_time < later.appointment_end_time
+        AND earlier.appointment_end_time > later.appointment_start_time
+    WHERE earlier.status IN ('pending', 'confirmed');
+
+    IF clashes IS NOT NULL THEN
+        RAISE EXCEPTION 'Active appointments overlap, resolve them before preventing overlaps'
+        USING
+            DETAIL = clashes,
+            HINT = 'Cancel or reschedule one appointment of each pair, then run the migrations again.';
+    END IF;
+END $$;
@@ -0,0 +1,15 @@
+CREATE EXTENSION IF NOT EXISTS btree_gist;
+
+-- Two active bookings of the same business can never overlap,
+-- even if the application level check is raced
+ALTER TABLE appointments
+ADD CONSTRAINT appointments_no_overlap EXCLUDE USING gist (
+    business_id
+    WITH
+        =,
+        tstzrange (appointment_start_time, appointment_end_time)
+    WITH
+        &&
+)
+WHERE
+    (status IN ('pending', 'confirmed'));
@@ -0,0 +1,3 @@
+ALTER TABLE services
+ADD COLUMN IF NOT EXISTS buffer_before_minutes INT NOT NULL DEFAULT 0 CHECK (buffer_before_minutes >= 0),
+ADD COLUMN IF NOT EXISTS buffer_after_minutes INT NOT NULL DEFAULT 0 CHECK (buffer_after_minutes >= 0);
@@ -0,0 +1,6 @@
+ALTER TABLE users
+ADD COLUMN IF NOT EXISTS slot_interval_minutes INT NOT NULL DEFAULT 30 CHECK (slot_interval_minutes > 0);
+
+-- NULL means the service follows the business interval
+ALTER TABLE services
+ADD COLUMN IF NOT EXISTS slot_interval_minutes INT CHECK (slot_interval_minutes > 0);
@@ -0,0 +1,8 @@
+ALTER TABLE users
+ADD COLUMN IF NOT EXISTS min_notice_minutes INT NOT NULL DEFAULT 0 CHECK (min_notice_minutes >= 0),
+ADD COLUMN IF NOT EXISTS max_advance_days INT NOT NULL DEFAULT 365 CHECK (max_advance_days > 0);
+
+-- NULL means the service follows the business setting
+ALTER TABLE services
+ADD COLUMN IF NOT EXISTS min_notice_minutes INT CHECK (min_notice_minutes >= 0),
+ADD COLUMN IF NOT EXISTS max_advance_days INT CHECK (max_advance_days > 0);
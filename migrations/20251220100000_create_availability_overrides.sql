@@ -0,0 +1,27 @@
+-- A date either has no override rows, one row with no times (closed all day)
+-- or one row per custom opening window, which replace the weekly rules
+CREATE TABLE
+    IF NOT EXISTS availability_overrides (
+        id UUID PRIMARY KEY DEFAULT uuid_generate_v4 (),
+        user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+        override_date DATE NOT NULL,
+        open_time TIME,
+        close_time TIME,
+        created_at TIMESTAMPTZ DEFAULT NOW (),
+        updated_at TIMESTAMPTZ DEFAULT NOW (),
+        --
+        CHECK (
+            (
+                open_time IS NULL
+                AND close_time IS NULL
+            )
+            OR (
+                open_time IS NOT NULL
+                AND close_time IS NOT NULL
+                AND open_time < close_time
+            )
+        ),
+        UNIQUE (user_id, override_date, open_time)
+    );
+
+CREATE INDEX IF NOT EXISTS availability_overrides_user_date_idx ON availability_overrides (user_id, override_date);
@@ -0,0 +1,16 @@
+-- A close time earlier than the open time now means the window runs past midnight
+ALTER TABLE availability_overrides
+DROP CONSTRAINT IF EXISTS availability_overrides_check;
+
+ALTER TABLE availability_overrides
+ADD CONSTRAINT availability_overrides_hours_check CHECK (
+    (
+        open_time IS NULL
+        AND close_time IS NULL
+    )
+    OR (
+        open_time IS NOT NULL
+        AND close_time IS NOT NULL
+        AND open_time <> close_time
+    )
+);
@@ -0,0 +1,24 @@
+ALTER TABLE users
+ADD COLUMN IF NOT EXISTS time_zone VARCHAR(50) NOT NULL DEFAULT 'UTC';
+
+-- Rules were read through their first row, keep that zone for each business
+UPDATE users u
+SET
+    time_zone = ba.time_zone
+FROM
+    (
+        SELECT DISTINCT
+            ON (user_id) user_id,
+            time_zone
+        FROM
+            business_availability
+        ORDER BY
+            user_id,
+            day_of_week,
+            open_time
+    ) ba
+WHERE
+    ba.user_id = u.id;
+
+ALTER TABLE business_availability
+DROP COLUMN IF EXISTS time_zone;
@@ -0,0 +1,16 @@
+CREATE TABLE
+    IF NOT EXISTS business_breaks (
+        id UUID PRIMARY KEY DEFAULT uuid_generate_v4 (),
+        user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+        day_of_week INT NOT NULL CHECK (
+            day_of_week >= 1
+            AND day_of_week <= 7
+        ),
+        start_time TIME NOT NULL,
+        end_time TIME NOT NULL,
+        created_at TIMESTAMPTZ DEFAULT NOW (),
+        updated_at TIMESTAMPTZ DEFAULT NOW (),
+        --
+        CHECK (start_time < end_time),
+        UNIQUE (user_id, day_of_week, start_time)
+    );
@@ -0,0 +1,16 @@
+CREATE TABLE
+    IF NOT EXISTS service_availability (
+        id UUID PRIMARY KEY DEFAULT uuid_generate_v4 (),
+        service_id UUID NOT NULL REFERENCES services (id) ON DELETE CASCADE,
+        day_of_week INT NOT NULL CHECK (
+            day_of_week >= 1
+            AND day_of_week <= 7
+        ),
+        open_time TIME NOT NULL,
+        close_time TIME NOT NULL,
+        created_at TIMESTAMPTZ DEFAULT NOW (),
+        updated_at TIMESTAMPTZ DEFAULT NOW (),
+        --
+        CHECK (open_time <> close_time),
+        UNIQUE (service_id, day_of_week, open_time)
+    );
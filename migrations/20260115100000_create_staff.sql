@@ -0,0 +1,60 @@
+CREATE TABLE
+    IF NOT EXISTS staff (
+        id UUID PRIMARY KEY DEFAULT uuid_generate_v4 (),
+        business_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+        name VARCHAR(255) NOT NULL,
+        email VARCHAR(255),
+        google_calendar_id VARCHAR(255),
+        is_active BOOLEAN NOT NULL DEFAULT TRUE,
+        created_at TIMESTAMPTZ DEFAULT NOW (),
+        updated_at TIMESTAMPTZ DEFAULT NOW ()
+    );
+
+CREATE TABLE
+    IF NOT EXISTS staff_services (
+        staff_id UUID NOT NULL REFERENCES staff (id) ON DELETE CASCADE,
+        service_id UUID NOT NULL REFERENCES services (id) ON DELETE CASCADE,
+        --
+        PRIMARY KEY (staff_id, service_id)
+    );
+
+CREATE TABLE
+    IF NOT EXISTS staff_availability (
+        id UUID PRIMARY KEY DEFAULT uuid_generate_v4 (),
+        staff_id UUID NOT NULL REFERENCES staff (id) ON DELETE CASCADE,
+        day_of_week INT NOT NULL CHECK (
+            day_of_week >= 1
+            AND day_of_week <= 7
+        ),
+        open_time TIME NOT NULL,
+        close_time TIME NOT NULL,
+        created_at TIMESTAMPTZ DEFAULT NOW (),
+        updated_at TIMESTAMPTZ DEFAULT NOW (),
+        --
+        CHECK (open_time <> close_time),
+        UNIQUE (staff_id, day_of_week, open_time)
+    );
+
+-- Staff are deactivated rather than deleted, so their bookings keep them
+ALTER TABLE appointments
+ADD COLUMN IF NOT EXISTS staff_id UUID REFERENCES staff (id);
+
+-- Bookings of different staff members may run in parallel, a business
+-- without staff keeps booking everything on one shared lane
+ALTER TABLE appointments
+DROP CONSTRAINT IF EXISTS appointments_no_overlap;
+
+ALTER TABLE appointments
+ADD CONSTRAINT appointments_no_overlap EXCLUDE USING gist (
+    business_id
+    WITH
+        =,
+        COALESCE(staff_id, '00000000-0000-0000-0000-000000000000'::UUID)
+    WITH
+        =,
+        tstzrange (appointment_start_time, appointment_end_time)
+    WITH
+        &&
+)
+WHERE
+    (status IN ('pending', 'confirmed'));
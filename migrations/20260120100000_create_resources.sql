@@ -0,0 +1,28 @@
+CREATE TABLE
+    IF NOT EXISTS resources (
+        id UUID PRIMARY KEY DEFAULT uuid_generate_v4 (),
+        business_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+        name VARCHAR(255) NOT NULL,
+        capacity INT NOT NULL DEFAULT 1 CHECK (capacity >= 1),
+        created_at TIMESTAMPTZ DEFAULT NOW (),
+        updated_at TIMESTAMPTZ DEFAULT NOW ()
+    );
+
+-- Every resource a service needs for each booking
+CREATE TABLE
+    IF NOT EXISTS service_resources (
+        service_id UUID NOT NULL REFERENCES services (id) ON DELETE CASCADE,
+        resource_id UUID NOT NULL REFERENCES resources (id) ON DELETE CASCADE,
+        --
+        PRIMARY KEY (service_id, resource_id)
+    );
+
+-- Taken when the booking is made, so later changes to a service leave
+-- existing bookings holding what they reserved
+CREATE TABLE
+    IF NOT EXISTS appointment_resources (
+        appointment_id UUID NOT NULL REFERENCES appointments (id) ON DELETE CASCADE,
+        resource_id UUID NOT NULL REFERENCES resources (id) ON DELETE CASCADE,
+        --
+        PRIMARY KEY (appointment_id, resource_id)
+    );
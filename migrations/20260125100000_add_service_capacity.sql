@@ -0,0 +1,31 @@
+-- Group classes take up to `capacity` bookings at the same time
+ALTER TABLE services
+ADD COLUMN IF NOT EXISTS capacity INT NOT NULL DEFAULT 1 CHECK (capacity >= 1);
+
+-- Seats booked in the same group session share its id, one-to-one
+-- bookings leave it empty
+ALTER TABLE appointments
+ADD COLUMN IF NOT EXISTS session_id UUID;
+
+-- Overlapping bookings of a lane still clash, unless they are seats of the
+-- same session. The seat count itself is checked under the business lock.
+ALTER TABLE appointments
+DROP CONSTRAINT IF EXISTS appointments_no_overlap;
+
+ALTER TABLE appointments
+ADD CONSTRAINT appointments_no_overlap EXCLUDE USING gist (
+    business_id
+    WITH
+        =,
+        COALESCE(staff_id, '00000000-0000-0000-0000-000000000000'::UUID)
+    WITH
+        =,
+        tstzrange (appointment_start_time, appointment_end_time)
+    WITH
+        &&,
+        COALESCE(session_id, id)
+    WITH
+        <>
+)
+WHERE
+    (status IN ('pending', 'confirmed'));
@@ -0,0 +1,23 @@
+-- A booking repeated every one or two weeks, each occurrence is an
+-- appointment of its own
+CREATE TABLE
+    IF NOT EXISTS appointment_series (
+        id UUID PRIMARY KEY DEFAULT uuid_generate_v4 (),
+        business_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+        service_id UUID NOT NULL REFERENCES services (id) ON DELETE CASCADE,
+        frequency VARCHAR(20) NOT NULL CHECK (frequency IN ('weekly', 'biweekly')),
+        occurrence_count INT CHECK (occurrence_count >= 1),
+        until_date DATE,
+        -- The single recurring event standing for the whole series
+        google_event_id VARCHAR(1024),
+        google_calendar_id VARCHAR(255),
+        created_at TIMESTAMPTZ DEFAULT NOW (),
+        updated_at TIMESTAMPTZ DEFAULT NOW (),
+        --
+        CHECK ((occurrence_count IS NULL) <> (until_date IS NULL))
+    );
+
+ALTER TABLE appointments
+ADD COLUMN IF NOT EXISTS series_id UUID REFERENCES appointment_series (id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS appointments_series_start_idx ON appointments (series_id, appointment_start_time);
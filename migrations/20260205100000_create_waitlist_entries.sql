@@ -0,0 +1,20 @@
+-- Customers waiting for an opening of a service within a date range, told
+-- about cancelled or moved bookings in the order they joined
+CREATE TABLE
+    IF NOT EXISTS waitlist_entries (
+        id UUID PRIMARY KEY DEFAULT uuid_generate_v4 (),
+        business_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+        service_id UUID NOT NULL REFERENCES services (id) ON DELETE CASCADE,
+        customer_name VARCHAR(255) NOT NULL,
+        customer_email VARCHAR(255),
+        customer_phone VARCHAR(20),
+        preferred_from DATE NOT NULL,
+        preferred_to DATE NOT NULL,
+        notified_at TIMESTAMPTZ,
+        created_at TIMESTAMPTZ DEFAULT NOW (),
+        --
+        CHECK (preferred_to >= preferred_from),
+        CHECK (customer_email IS NOT NULL OR customer_phone IS NOT NULL)
+    );
+
+CREATE INDEX IF NOT EXISTS waitlist_entries_business_service_created_idx ON waitlist_entries (business_id, service_id, created_at);
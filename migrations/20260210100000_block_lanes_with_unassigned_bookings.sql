@@ -0,0 +1,55 @@
+-- Bookings made before the business had staff belong to no lane, they keep
+-- every staff member busy. Clashes already made with them are customer
+-- bookings, so the migration does not cancel any of them, it stops and lists
+-- every clashing pair instead. Once the business has cancelled, moved or
+-- assigned one booking of each pair, run the migrations again.
+DO $$
+DECLARE
+    clashes TEXT;
+BEGIN
+    SELECT string_agg(
+        format(
+            'business %s: unassigned appointment %s overlaps appointment %s of staff %s',
+            unassigned.business_id,
+            unassigned.id,
+            staffed.id,
+            staffed.staff_id
+        ),
+        E'\n'
+        ORDER BY unassigned.business_id, unassigned.appointment_start_time, unassigned.id, staffed.id
+    )
+    INTO clashes
+    FROM appointments unassigned
+    JOIN appointments staffed
+        ON staffed.business_id = unassigned.business_id
+        AND staffed.staff_id IS NOT NULL
+        AND staffed.status IN ('pending', 'confirmed')
+        AND unassigned.appointment_start_time < staffed.appointment_end_time
+        AND unassigned.appointment_end_time > staffed.appointment_start_time
+    WHERE
+        unassigned.staff_id IS NULL
+        AND unassigned.status IN ('pending', 'confirmed');
+
+    IF clashes IS NOT NULL THEN
+        RAISE EXCEPTION 'Unassigned appointments overlap staff appointments, resolve them before blocking every lane'
+        USING
+            DETAIL = clashes,
+            HINT = 'Cancel, reschedule or assign one appointment of each pair, then run the migrations again.';
+    END IF;
+END $$;
+
+-- Unassigned bookings clash with those of any staff member
+ALTER TABLE appointments
+ADD CONSTRAINT appointments_unassigned_no_overlap EXCLUDE USING gist (
+    business_id
+    WITH
+        =,
+        (staff_id IS NULL)
+    WITH
+        <>,
+        tstzrange (appointment_start_time, appointment_end_time)
+    WITH
+        &&
+)
+WHERE
+    (status IN ('pending', 'confirmed'));
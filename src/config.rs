@@ -1,4 +1,4 @@
-use std::env;
+use std::{env, net::IpAddr};
 
 #[derive(Clone)]
 pub struct Config {
@@ -10,6 +10,9 @@ pub struct Config {
     pub port: u16,
     pub gcs_bucket_name: String,
     pub redis_url: String,
+    pub notifier: String, // "log" or "file"
+    pub notifier_file_path: String,
+    pub trusted_proxies: Vec<IpAddr>, // Whose X-Forwarded-For is believed
 }
 
 impl Config {
@@ -29,6 +32,23 @@ impl Config {
             .parse::<u16>()
             .expect("PORT must be a valid number");
 
+        let notifier = get_env_or_default("NOTIFIER", "log");
+
+        if !["log", "file"].contains(&notifier.as_str()) {
+            panic!("NOTIFIER must be either `log` or `file`");
+        }
+
+        let trusted_proxies = get_env_or_default("TRUSTED_PROXIES", "")
+            .split(',')
+            .map(str::trim)
+            .filter(|proxy| !proxy.is_empty())
+            .map(|proxy| {
+                proxy
+                    .parse::<IpAddr>()
+                    .expect("TRUSTED_PROXIES must be a comma separated list of IP addresses")
+            })
+            .collect();
+
         Self {
             database_url: expect_env("DATABASE_URL"),
             jwt_secret: expect_env("JWT_SECRET"),
@@ -37,6 +57,12 @@ impl Config {
             google_redirect_uri: expect_env("GOOGLE_REDIRECT_URI"),
             gcs_bucket_name: expect_env("GCS_BUCKET_NAME"),
             redis_url: expect_env("REDIS_URL"),
+            notifier_file_path: get_env_or_default(
+                "NOTIFIER_FILE_PATH",
+                "waitlist_notifications.log",
+            ),
+            notifier,
+            trusted_proxies,
             port,
         }
     }
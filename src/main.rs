@@ -10,11 +10,14 @@ mod tests;
 use crate::{
     config::Config,
     routes::{
-        appointment_routes, auth_routes, service_routes, user_routes,
+        appointment_routes, auth_routes, resource_routes, service_routes, staff_routes,
+        user_routes,
         utils_routes::{home, route_not_found},
+        waitlist_routes,
     },
     utils::{
         api_doc::ApiDoc,
+        notifier_utils::build_notifier,
         response_utils::{json_error_handler, path_error_handler, query_error_handler},
     },
 };
@@ -31,6 +34,7 @@ async fn main() -> std::io::Result<()> {
     let http_client = reqwest::Client::new();
     let redis_cfg = RedisConfig::from_url(&config.redis_url);
     let redis_pool = redis_cfg.create_pool(Some(Runtime::Tokio1)).unwrap();
+    let notifier = build_notifier(&config);
 
     let pool = PgPoolOptions::new()
         .max_connections(5)
@@ -59,13 +63,17 @@ async fn main() -> std::io::Result<()> {
             .app_data(web::Data::new(redis_pool.clone()))
             .app_data(web::Data::new(pool.clone()))
             .app_data(web::Data::new(http_client.clone()))
+            .app_data(web::Data::from(notifier.clone()))
             .app_data(path_config)
             .app_data(json_config)
             .app_data(query_config)
             .configure(auth_routes::auth_config)
             .configure(user_routes::user_config)
             .configure(service_routes::service_config)
+            .configure(staff_routes::staff_config)
+            .configure(resource_routes::resource_config)
             .configure(appointment_routes::appointment_config)
+            .configure(waitlist_routes::waitlist_config)
             .service(home)
             .service(
                 SwaggerUi::new("/swagger-ui/{_:.*}")
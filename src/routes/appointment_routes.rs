@@ -1,24 +1,69 @@
 use crate::{
     config::Config,
+    middlewares::auth_middleware::AuthenticatedUser,
     routes::utils_routes::{
-        bad_request_response, expectation_failed_response, internal_server_error_response,
-        not_found_response,
+        BookingError, bad_request_response, conflict_reponse, expectation_failed_response,
+        internal_server_error_response, not_found_response, unprocessable_entity_response,
     },
     structs::{
         db_struct::{
-            Appointment, Auth, AvailabilityRule, CreateAppointment, GoogleCalendarEvent,
-            GoogleEventAttendee, GoogleEventDateTime, Service,
+            Appointment, AppointmentSeries, AppointmentSeriesResult, AppointmentStatus, Auth,
+            CancelAppointment, CancelScope, CreateAppointment, CreateAppointmentSeries,
+            FailedOccurrence, GoogleCalendarEvent, GoogleEventAttendee, GoogleEventDateTime,
+            GoogleEventExtendedProperties, GoogleEventItem, GoogleEventTimesPatch,
+            RescheduleAppointment, Service, Staff,
         },
         response_struct::ApiResponse,
+        util_struct::{AppointmentQuery, CreateSlotHold, IdempotencyRecord, SlotHold},
     },
-    utils::{auth_utils::get_new_access_token, others_utils::convert_to_local_primitive},
+    utils::{
+        auth_utils::get_new_access_token,
+        availability_utils::{
+            booking_lanes, breaks_between, business_time_zone, opening_hours_for_booking,
+            resource_blocked_periods, shared_calendar_bookings,
+        },
+        cache_utils::invalidate_booked_slot_cache,
+        calendar_utils::{
+            PRIMARY_CALENDAR, busy_calendar, create_calendar_event, delete_calendar_event,
+            event_spans, fetch_busy_periods, find_appointment_event_id, get_calendar_event,
+            patch_calendar_event_recurrence, patch_calendar_event_times,
+        },
+        hold_utils::{
+            HOLD_TTL_MINUTES, MAX_HOLDS_PER_BUSINESS, business_holds, find_hold, release_hold,
+            save_hold,
+        },
+        idempotency_utils::{
+            IdempotencyOutcome, MAX_IDEMPOTENCY_KEY_LENGTH, claim_idempotency_key,
+            idempotency_outcome, release_idempotency_key, store_idempotent_response,
+        },
+        notifier_utils::Notifier,
+        others_utils::{
+            AmbiguousTime, booking_window, check_hold_limit, client_ip, convert_to_local_primitive,
+            end_recurrence_before, fits_opening_hours, held_seats, order_lane_candidates,
+            recurring_instance_id, resolve_local_time, series_local_starts, series_recurrence,
+            subtract_periods,
+        },
+        waitlist_utils::notify_waitlist,
+    },
+};
+use actix_web::{
+    HttpRequest, HttpResponse, Responder, ResponseError, body::BoxBody, http::StatusCode, web,
+};
+use chrono_tz::Tz;
+use sqlx::{Acquire, PgPool, Postgres, Transaction};
+use std::{collections::HashMap, str::FromStr};
+use time::{
+    Date, Duration, OffsetDateTime, format_description::well_known::Rfc3339,
+    macros::format_description,
 };
-use actix_web::{HttpResponse, Responder, web};
-use deadpool_redis::redis;
-use sqlx::PgPool;
-use time::{Duration, format_description::well_known::Rfc3339};
 use uuid::Uuid;
 
+// About a year of weekly bookings, in one series and one Google event
+const MAX_SERIES_OCCURRENCES: usize = 52;
+
+// Longest range asked of Google free/busy in one go
+const FREE_BUSY_CHUNK_DAYS: i64 = 60;
+
 /* -------------------------------------------------------------------------- */
 /*                                      -                                     */
 /* -------------------------------------------------------------------------- */
@@ -27,14 +72,20 @@ use uuid::Uuid;
     post,
     path = "/appointments",
     tag = "Appointments",
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Retries with the same key and payload for the same business get the original response for 24 hours")
+    ),
     request_body = CreateAppointment,
     responses(
         (status = 201, body = ApiResponse<Appointment>),
         (status = 400, description = "Bad Request"),
+        (status = 409, description = "Slot Already Taken"),
+        (status = 422, description = "Idempotency-Key Reused With Another Payload"),
         (status = 500, description = "Internal Server Error")
     )
 )]
 pub async fn create_appointment(
+    req: HttpRequest,
     config: web::Data<Config>,
     pool: web::Data<PgPool>,
     body: web::Json<CreateAppointment>,
@@ -43,297 +94,386 @@ pub async fn create_appointment(
 ) -> impl Responder {
     let new_appt = body.into_inner();
 
-    // Start Transaction
-    let mut tx = match pool.begin().await {
-        Ok(tx) => tx,
-        Err(e) => {
-            return internal_server_error_response(format!("Failed to start transaction: {}", e));
-        }
+    let idempotency_key = match req.headers().get("Idempotency-Key") {
+        Some(value) => match value.to_str().map(str::trim) {
+            Ok(key) if !key.is_empty() && key.len() <= MAX_IDEMPOTENCY_KEY_LENGTH => {
+                key.to_string()
+            }
+
+            _ => {
+                return bad_request_response(format!(
+                    "Idempotency-Key must be between 1 and {} visible characters.",
+                    MAX_IDEMPOTENCY_KEY_LENGTH
+                ));
+            }
+        },
+
+        None => return book_appointment(config, pool, new_appt, http_client, redis_pool).await,
     };
 
-    // Fetch Service to know the duration
-    let service = match sqlx::query_as!(
-        Service,
-        r#"SELECT * FROM services WHERE id = $1"#,
-        new_appt.service_id
-    )
-    .fetch_one(&mut *tx)
-    .await
-    {
-        Ok(s) => s,
+    // A retry carries the same payload, anything else is a different request
+    let payload = match serde_json::to_value(&new_appt) {
+        Ok(payload) => payload,
+        Err(e) => return internal_server_error_response(e.to_string()),
+    };
 
-        Err(sqlx::Error::RowNotFound) => {
-            tx.rollback().await.ok();
-            return bad_request_response("Invalid service_id.".to_string());
+    let business_id = new_appt.business_id;
+
+    match claim_idempotency_key(&redis_pool, business_id, &idempotency_key, &payload).await {
+        Ok(None) => {}
+
+        Ok(Some(record)) => {
+            return match idempotency_outcome(record, &payload) {
+                IdempotencyOutcome::Replay(response) => HttpResponse::Created().json(response),
+
+                IdempotencyOutcome::InProgress => conflict_reponse(
+                    "A request with this Idempotency-Key is still being processed.".to_string(),
+                ),
+
+                IdempotencyOutcome::PayloadMismatch => unprocessable_entity_response(
+                    "This Idempotency-Key was already used with a different payload.".to_string(),
+                ),
+            };
         }
 
+        Err(e) => return internal_server_error_response(e),
+    }
+
+    let response = book_appointment(config, pool, new_appt, http_client, redis_pool.clone()).await;
+
+    if response.status() != StatusCode::CREATED {
+        release_idempotency_key(&redis_pool, business_id, &idempotency_key).await;
+        return response;
+    }
+
+    // The booking is made either way, replays are only as good as the store.
+    // Without a stored response the key is released rather than left pending.
+    let (response, body) = response.into_parts();
+
+    let body = match actix_web::body::to_bytes(body).await {
+        Ok(body) => body,
         Err(e) => {
-            tx.rollback().await.ok();
+            release_idempotency_key(&redis_pool, business_id, &idempotency_key).await;
             return internal_server_error_response(e.to_string());
         }
     };
 
-    // Fetch Business Auth for Google Token
-    let auth_record = match sqlx::query_as!(
-        Auth,
-        r#"SELECT * FROM auth WHERE user_id = $1"#,
-        new_appt.business_id
-    )
-    .fetch_one(&mut *tx)
-    .await
-    {
-        Ok(a) => a,
+    let stored = match serde_json::from_slice(&body) {
+        Ok(stored) => {
+            let record = IdempotencyRecord {
+                payload,
+                response: Some(stored),
+            };
 
-        Err(sqlx::Error::RowNotFound) => {
-            tx.rollback().await.ok();
-            return bad_request_response("Business not found or not authenticated.".to_string());
+            store_idempotent_response(&redis_pool, business_id, &idempotency_key, &record).await
         }
 
+        Err(e) => Err(e.to_string()),
+    };
+
+    if let Err(e) = stored {
+        eprintln!("Failed to store the idempotent response: {}", e);
+        release_idempotency_key(&redis_pool, business_id, &idempotency_key).await;
+    }
+
+    response.set_body(BoxBody::new(body))
+}
+
+// The booking itself, without the Idempotency-Key handling around it
+async fn book_appointment(
+    config: web::Data<Config>,
+    pool: web::Data<PgPool>,
+    new_appt: CreateAppointment,
+    http_client: web::Data<reqwest::Client>,
+    redis_pool: web::Data<deadpool_redis::Pool>,
+) -> HttpResponse {
+    // Start Transaction
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
         Err(e) => {
-            tx.rollback().await.ok();
-            return internal_server_error_response(e.to_string());
+            return internal_server_error_response(format!("Failed to start transaction: {}", e));
         }
     };
 
-    // Check Business Active Status
-    let is_active = sqlx::query_scalar!(
-        r#"SELECT is_active as "is_active!: bool" FROM users WHERE id = $1"#,
-        new_appt.business_id
+    let (service, auth_record) =
+        match load_booking_target(&mut tx, new_appt.business_id, new_appt.service_id).await {
+            Ok(target) => target,
+
+            Err(response) => {
+                tx.rollback().await.ok();
+                return response;
+            }
+        };
+
+    // A hold guarantees one slot, the booking has to be for exactly that slot
+    let staff_id = match new_appt.hold_token {
+        Some(hold_token) => match find_hold(&redis_pool, new_appt.business_id, hold_token).await {
+            Ok(Some(hold))
+                if hold.service_id == new_appt.service_id
+                    && hold.start_time == new_appt.appointment_start_time
+                    && new_appt
+                        .staff_id
+                        .is_none_or(|staff_id| hold.staff_id == Some(staff_id)) =>
+            {
+                hold.staff_id
+            }
+
+            Ok(Some(_)) => {
+                tx.rollback().await.ok();
+                return bad_request_response("The hold is for a different slot.".to_string());
+            }
+
+            Ok(None) => {
+                tx.rollback().await.ok();
+                return conflict_reponse(
+                    "This hold has expired, please pick a slot again.".to_string(),
+                );
+            }
+
+            Err(e) => {
+                tx.rollback().await.ok();
+                return internal_server_error_response(e);
+            }
+        },
+
+        None => new_appt.staff_id,
+    };
+
+    let holds = match lock_business_holds(
+        &mut tx,
+        &redis_pool,
+        new_appt.business_id,
+        new_appt.hold_token,
     )
-    .fetch_one(&mut *tx)
     .await
-    .unwrap_or(false);
-
-    if !is_active {
-        tx.rollback().await.ok();
+    {
+        Ok(holds) => holds,
 
-        return bad_request_response(
-            "This business is not currently accepting appointments.".to_string(),
-        );
-    }
+        Err(response) => {
+            tx.rollback().await.ok();
+            return response;
+        }
+    };
 
     // Calculate Time And Check Availability
     let start_time = new_appt.appointment_start_time;
     let duration = service.duration_minutes.unwrap_or(30);
     let end_time = start_time + Duration::minutes(duration as i64);
-    let weekday = start_time.weekday().number_from_monday() as i32;
 
-    let rules = match sqlx::query_as!(
-        AvailabilityRule,
-        r#"SELECT * FROM business_availability WHERE user_id = $1 AND day_of_week = $2"#,
+    let (candidates, lane_count) = match check_booking_lanes(
+        &mut tx,
         new_appt.business_id,
-        weekday
+        &service,
+        staff_id,
+        (start_time, end_time),
+        &holds,
     )
-    .fetch_all(&mut *tx)
     .await
     {
-        Ok(r) => r,
+        Ok(checked) => checked,
 
-        Err(e) => {
+        Err(error) => {
             tx.rollback().await.ok();
-
-            return internal_server_error_response(e.to_string());
+            return error.error_response();
         }
     };
 
-    if rules.is_empty() {
+    let Some(refresh_token) = auth_record.refresh_token else {
         tx.rollback().await.ok();
 
-        return bad_request_response("Business is closed on this day.".to_string());
-    }
+        let message = format!(
+            "Info: Business {} has no Google Calendar connected.",
+            new_appt.business_id
+        );
 
-    // Conversion and Comparison Logic
-    let first_rule = &rules[0];
+        return expectation_failed_response(message);
+    };
 
-    let local_start = match convert_to_local_primitive(start_time, &first_rule.time_zone) {
-        Ok(dt) => dt.time(),
+    let access_token = match get_new_access_token(config, &http_client, refresh_token).await {
+        Ok(token) => token,
 
         Err(e) => {
             tx.rollback().await.ok();
 
-            return internal_server_error_response(e);
+            return internal_server_error_response(format!(
+                "Failed to refresh Google token: {}",
+                e
+            ));
         }
     };
 
-    let local_end = match convert_to_local_primitive(end_time, &first_rule.time_zone) {
-        Ok(dt) => dt.time(),
+    // A hold was checked against the calendar when it was handed out, the slot
+    // is the customer's until it expires
+    let picked = match new_appt.hold_token {
+        Some(_) => candidates
+            .into_iter()
+            .next()
+            .ok_or_else(|| no_free_lane_error(lane_count, None)),
 
-        Err(e) => {
-            tx.rollback().await.ok();
-
-            return internal_server_error_response(e);
+        None => {
+            pick_free_lane(
+                &mut tx,
+                &http_client,
+                &access_token,
+                candidates,
+                lane_count,
+                buffered_window(&service, start_time, end_time),
+            )
+            .await
         }
     };
 
-    let business_is_available = rules
-        .iter()
-        .any(|rule| local_start >= rule.open_time && local_end <= rule.close_time);
+    let (staff, joined_session) = match picked {
+        Ok(lane) => lane,
 
-    if !business_is_available {
-        tx.rollback().await.ok();
+        Err(error) => {
+            tx.rollback().await.ok();
+            return error.error_response();
+        }
+    };
 
-        return bad_request_response("Requested slot is outside operating hours.".to_string());
-    }
+    let event_calendar = busy_calendar(staff.as_ref());
+
+    // The first seat of a group class opens its session
+    let session_id = joined_session.or((service.capacity > 1).then(Uuid::new_v4));
 
     // Save Appointment to Database
-    let appointment = match sqlx::query_as!(
-        Appointment,
-        r#"
-        INSERT INTO appointments (
-            service_id, business_id, customer_name, customer_email, 
-            customer_phone, appointment_start_time, notes, appointment_end_time
-        )
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-        RETURNING *
-        "#,
-        new_appt.service_id,
-        new_appt.business_id,
-        new_appt.customer_name,
-        new_appt.customer_email,
-        new_appt.customer_phone,
-        start_time,
-        new_appt.notes,
-        end_time
+    let mut appointment = match insert_appointment(
+        &mut tx,
+        &new_appt,
+        end_time,
+        staff.as_ref().map(|staff| staff.id),
+        session_id,
+        None,
     )
-    .fetch_one(&mut *tx)
     .await
     {
         Ok(appt) => appt,
 
-        Err(e) => {
+        Err(error) => {
             tx.rollback().await.ok();
-            return internal_server_error_response(e.to_string());
+            return error.error_response();
         }
     };
 
     // Google Calendar Sync
-    if let Some(refresh_token) = auth_record.refresh_token {
-        let access_token = match get_new_access_token(config, &http_client, refresh_token).await {
-            Ok(token) => token,
-
-            Err(e) => {
-                tx.rollback().await.ok();
 
-                return internal_server_error_response(format!(
-                    "Failed to refresh Google token: {}",
-                    e
-                ));
-            }
-        };
+    // Format Dates Safely
+    let start_fmt = match start_time.format(&Rfc3339) {
+        Ok(formatted_string) => formatted_string,
 
-        // Format Dates Safely
-        let start_fmt = match start_time.format(&Rfc3339) {
-            Ok(formatted_string) => formatted_string,
+        Err(e) => {
+            tx.rollback().await.ok();
 
-            Err(e) => {
-                tx.rollback().await.ok();
+            return internal_server_error_response(e.to_string());
+        }
+    };
 
-                return internal_server_error_response(e.to_string());
-            }
-        };
+    let end_fmt = match end_time.format(&Rfc3339) {
+        Ok(formatted_string) => formatted_string,
 
-        let end_fmt = match end_time.format(&Rfc3339) {
-            Ok(formatted_string) => formatted_string,
+        Err(e) => {
+            tx.rollback().await.ok();
 
-            Err(e) => {
-                tx.rollback().await.ok();
+            return internal_server_error_response(e.to_string());
+        }
+    };
 
-                return internal_server_error_response(e.to_string());
-            }
-        };
+    // Build Event
+    let notes_str = new_appt
+        .notes
+        .as_deref()
+        .map(|n| format!("\n\nNotes: {}", n))
+        .unwrap_or("N/A".to_string());
 
-        // Build Event
-        let notes_str = new_appt
-            .notes
-            .as_deref()
-            .map(|n| format!("\n\nNotes: {}", n))
-            .unwrap_or("N/A".to_string());
-
-        let event = GoogleCalendarEvent {
-            summary: format!(
-                "Appointment Scheduled: {} for {}",
-                service.service_name, new_appt.customer_name
-            ),
-            description: format!(
-                "Service: {}\nCustomer Phone: {}\nCustomer Email: {}\nNote: {}",
-                service.service_name,
-                new_appt.customer_phone.as_deref().unwrap_or("N/A"),
-                new_appt.customer_email.as_deref().unwrap_or("N/A"),
-                notes_str
-            ),
-            start: GoogleEventDateTime {
-                date_time: start_fmt,
-                time_zone: "UTC".to_string(),
-            },
-            end: GoogleEventDateTime {
-                date_time: end_fmt,
-                time_zone: "UTC".to_string(),
+    let event = GoogleCalendarEvent {
+        summary: format!(
+            "Appointment Scheduled: {} for {}",
+            service.service_name, new_appt.customer_name
+        ),
+        description: format!(
+            "Service: {}\nStaff: {}\nCustomer Phone: {}\nCustomer Email: {}\nNote: {}",
+            service.service_name,
+            staff.as_ref().map_or("N/A", |staff| staff.name.as_str()),
+            new_appt.customer_phone.as_deref().unwrap_or("N/A"),
+            new_appt.customer_email.as_deref().unwrap_or("N/A"),
+            notes_str
+        ),
+        start: GoogleEventDateTime {
+            date_time: start_fmt,
+            time_zone: "UTC".to_string(),
+        },
+        end: GoogleEventDateTime {
+            date_time: end_fmt,
+            time_zone: "UTC".to_string(),
+        },
+        attendees: vec![
+            // Add the customer as an attendee so they get an invite
+            GoogleEventAttendee {
+                email: new_appt.customer_email.unwrap_or_default(),
             },
-            attendees: vec![
-                // Add the customer as an attendee so they get an invite
-                GoogleEventAttendee {
-                    email: new_appt.customer_email.unwrap_or_default(),
-                },
-            ],
-        };
-
-        // Send to Google Calendar
-        let google_res = http_client
-            .post("https://www.googleapis.com/calendar/v3/calendars/primary/events?sendUpdates=all")
-            .bearer_auth(access_token)
-            .json(&event)
-            .send()
-            .await;
-
-        match google_res {
-            Ok(res) if res.status().is_success() => {}
+        ],
+        extended_properties: GoogleEventExtendedProperties {
+            private: HashMap::from([("appointment_id".to_string(), appointment.id.to_string())]),
+        },
+        recurrence: Vec::new(),
+    };
 
-            Ok(res) => {
-                // Google returned an error, maybe a 400 - Bad Request
-                let err_text = res.text().await.unwrap_or_default();
+    // Send to Google Calendar
+    let created_event =
+        match create_calendar_event(&http_client, &access_token, event_calendar, &event).await {
+            Ok(created) => created,
 
+            Err(e) => {
                 tx.rollback().await.ok();
-
-                return internal_server_error_response(format!(
-                    "Google Calendar API Error: {}",
-                    err_text
-                ));
+                return internal_server_error_response(e);
             }
+        };
 
-            Err(e) => {
-                tx.rollback().await.ok();
+    // Keep the event reference so it can be updated or removed later
+    let calendar_id = created_event
+        .organizer
+        .and_then(|o| o.email)
+        .unwrap_or(event_calendar.to_string());
 
-                return internal_server_error_response(format!("Failed to contact Google: {}", e));
-            }
-        }
-    } else {
-        let message = format!(
-            "Info: Business {} has no Google Calendar connected.",
-            new_appt.business_id
-        );
+    appointment = match sqlx::query_as!(
+        Appointment,
+        r#"
+        UPDATE appointments SET
+            google_event_id = $1,
+            google_calendar_id = $2,
+            google_event_etag = $3
+        WHERE id = $4
+        RETURNING *
+        "#,
+        created_event.id,
+        calendar_id,
+        created_event.etag,
+        appointment.id
+    )
+    .fetch_one(&mut *tx)
+    .await
+    {
+        Ok(appt) => appt,
 
-        return expectation_failed_response(message);
-    }
+        Err(e) => {
+            tx.rollback().await.ok();
+            return internal_server_error_response(e.to_string());
+        }
+    };
 
     if let Err(e) = tx.commit().await {
         return internal_server_error_response(format!("Failed to commit transaction: {}", e));
     }
 
-    // Cache result
-    let date_str = start_time.date().to_string(); // Helper to get YYYY-MM-DD
-    let cache_key = format!(
-        "slots:{}:{}:{}",
-        new_appt.business_id, date_str, new_appt.service_id
-    );
-
-    if let Ok(mut conn) = redis_pool.get().await {
-        let _: () = redis::cmd("DEL")
-            .arg(&cache_key)
-            .query_async(&mut conn)
-            .await
-            .unwrap_or(());
+    // The booking takes over from the hold
+    if let Some(hold_token) = new_appt.hold_token {
+        release_hold(&redis_pool, new_appt.business_id, hold_token).await;
     }
 
+    // Invalidate cached slots for that day
+    invalidate_booked_slot_cache(&redis_pool, &pool, new_appt.business_id, &[start_time]).await;
+
     let response = ApiResponse {
         success: true,
         data: Some(appointment),
@@ -353,44 +493,482 @@ pub async fn create_appointment(
 /* -------------------------------------------------------------------------- */
 
 #[utoipa::path(
-    get,
-    path = "/appointments/{id}",
+    post,
+    path = "/appointments/series",
     tag = "Appointments",
-    params(
-        ("id" = Uuid, Path, description = "Appointment ID")
-    ),
+    request_body = CreateAppointmentSeries,
     responses(
-        (status = 200, body = ApiResponse<Appointment>),
-        (status = 404, description = "Not Found"),
+        (status = 201, body = ApiResponse<AppointmentSeriesResult>),
         (status = 400, description = "Bad Request"),
+        (status = 409, description = "No Occurrence Could Be Booked"),
+        (status = 417, description = "Google Calendar Not Connected"),
         (status = 500, description = "Internal Server Error")
     )
 )]
-pub async fn get_appointment_by_id(
-    path: web::Path<Uuid>,
+pub async fn create_appointment_series(
+    config: web::Data<Config>,
     pool: web::Data<PgPool>,
+    body: web::Json<CreateAppointmentSeries>,
+    http_client: web::Data<reqwest::Client>,
+    redis_pool: web::Data<deadpool_redis::Pool>,
 ) -> impl Responder {
-    let appt_id = path.into_inner();
+    let CreateAppointmentSeries {
+        appointment: first_appt,
+        recurrence,
+    } = body.into_inner();
 
-    match sqlx::query_as!(
-        Appointment,
-        r#"SELECT * FROM appointments WHERE id = $1"#,
-        appt_id
-    )
-    .fetch_one(pool.get_ref())
-    .await
-    {
-        Ok(appointment) => {
-            let response = ApiResponse {
-                success: true,
-                data: Some(appointment),
-                message: None,
-            };
-            HttpResponse::Ok().json(response)
+    if first_appt.hold_token.is_some() {
+        return bad_request_response("A hold cannot be redeemed by a series.".to_string());
+    }
+
+    let until = match recurrence.until.as_deref() {
+        Some(until) => match Date::parse(until, format_description!("[year]-[month]-[day]")) {
+            Ok(date) => Some(date),
+            Err(_) => return bad_request_response("Invalid until date (YYYY-MM-DD)".to_string()),
+        },
+
+        None => None,
+    };
+
+    let count = match (recurrence.count, until) {
+        (Some(count), None) if (1..=MAX_SERIES_OCCURRENCES as i32).contains(&count) => {
+            Some(count as usize)
         }
-        Err(sqlx::Error::RowNotFound) => not_found_response("Appointment not found".to_string()),
-        Err(e) => internal_server_error_response(e.to_string()),
+
+        (Some(_), None) => {
+            return bad_request_response(format!(
+                "A series can have between 1 and {} occurrences.",
+                MAX_SERIES_OCCURRENCES
+            ));
+        }
+
+        (None, Some(_)) => None,
+
+        _ => {
+            return bad_request_response(
+                "A series ends either after a count or on an until date.".to_string(),
+            );
+        }
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            return internal_server_error_response(format!("Failed to start transaction: {}", e));
+        }
+    };
+
+    let (service, auth_record) =
+        match load_booking_target(&mut tx, first_appt.business_id, first_appt.service_id).await {
+            Ok(target) => target,
+
+            Err(response) => {
+                tx.rollback().await.ok();
+                return response;
+            }
+        };
+
+    // The whole series is synced as one event, so the calendar must be there
+    let Some(refresh_token) = auth_record.refresh_token else {
+        tx.rollback().await.ok();
+
+        let message = format!(
+            "Info: Business {} has no Google Calendar connected.",
+            first_appt.business_id
+        );
+
+        return expectation_failed_response(message);
+    };
+
+    let access_token = match get_new_access_token(config, &http_client, refresh_token).await {
+        Ok(token) => token,
+
+        Err(e) => {
+            tx.rollback().await.ok();
+
+            return internal_server_error_response(format!(
+                "Failed to refresh Google token: {}",
+                e
+            ));
+        }
+    };
+
+    let time_zone = match business_time_zone(&mut tx, first_appt.business_id).await {
+        Ok(z) => z,
+        Err(e) => {
+            tx.rollback().await.ok();
+            return internal_server_error_response(e.to_string());
+        }
+    };
+
+    let tz: Tz = match Tz::from_str(&time_zone) {
+        Ok(z) => z,
+        Err(_) => {
+            tx.rollback().await.ok();
+            return internal_server_error_response("Invalid DB Timezone".to_string());
+        }
+    };
+
+    let first_local =
+        match convert_to_local_primitive(first_appt.appointment_start_time, &time_zone) {
+            Ok(local) => local,
+            Err(e) => {
+                tx.rollback().await.ok();
+                return internal_server_error_response(e);
+            }
+        };
+
+    let interval_weeks = recurrence.frequency.interval_weeks();
+    let local_starts = series_local_starts(
+        first_local,
+        interval_weeks,
+        count,
+        until,
+        MAX_SERIES_OCCURRENCES,
+    );
+
+    if local_starts.is_empty() {
+        tx.rollback().await.ok();
+        return bad_request_response("The until date is before the first occurrence.".to_string());
+    }
+
+    if local_starts.len() > MAX_SERIES_OCCURRENCES {
+        tx.rollback().await.ok();
+
+        return bad_request_response(format!(
+            "A series can have at most {} occurrences.",
+            MAX_SERIES_OCCURRENCES
+        ));
+    }
+
+    let lanes = match booking_lanes(&mut tx, first_appt.business_id, service.id).await {
+        Ok(lanes) => lanes
+            .into_iter()
+            .filter(|lane| {
+                first_appt.staff_id.is_none()
+                    || lane.as_ref().map(|staff| staff.id) == first_appt.staff_id
+            })
+            .collect::<Vec<_>>(),
+
+        Err(e) => {
+            tx.rollback().await.ok();
+            return internal_server_error_response(e.to_string());
+        }
+    };
+
+    // Every occurrence lies between the first start and the last, give or take
+    // the offset changes in between, which a day of margin covers
+    let last_local = local_starts.last().copied().unwrap_or(first_local);
+    let last_start = first_appt.appointment_start_time + (last_local - first_local);
+    let duration = Duration::minutes(service.duration_minutes.unwrap_or(30) as i64);
+    let series_window = buffered_window(
+        &service,
+        first_appt.appointment_start_time,
+        last_start + duration + Duration::days(1),
+    );
+
+    // Fetched before the business lock below, Google is slow
+    let lane_busy =
+        match fetch_lane_busy_periods(&mut tx, &http_client, &access_token, &lanes, series_window)
+            .await
+        {
+            Ok(lane_busy) => lane_busy,
+
+            Err(response) => {
+                tx.rollback().await.ok();
+                return response;
+            }
+        };
+
+    let mut series = match sqlx::query_as!(
+        AppointmentSeries,
+        r#"
+        INSERT INTO appointment_series (
+            business_id, service_id, frequency, occurrence_count, until_date
+        )
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING *
+        "#,
+        first_appt.business_id,
+        service.id,
+        recurrence.frequency.as_str(),
+        recurrence.count,
+        until
+    )
+    .fetch_one(&mut *tx)
+    .await
+    {
+        Ok(series) => series,
+
+        Err(e) => {
+            tx.rollback().await.ok();
+            return internal_server_error_response(e.to_string());
+        }
+    };
+
+    let holds = match lock_business_holds(&mut tx, &redis_pool, first_appt.business_id, None).await
+    {
+        Ok(holds) => holds,
+
+        Err(response) => {
+            tx.rollback().await.ok();
+            return response;
+        }
+    };
+
+    let local_format = format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]");
+    let mut series_staff: Option<Option<Staff>> = None;
+    let mut appointments = Vec::new();
+    let mut failed_occurrences = Vec::new();
+    let mut skipped_local_starts = Vec::new();
+
+    for local_start in local_starts {
+        let local_start_time = local_start.format(&local_format).unwrap_or_default();
+
+        let Some(start_time) = resolve_local_time(local_start, &tz, AmbiguousTime::Earliest) else {
+            failed_occurrences.push(FailedOccurrence {
+                local_start_time,
+                appointment_start_time: None,
+                reason: "This local time does not exist on that day.".to_string(),
+            });
+            skipped_local_starts.push(local_start);
+            continue;
+        };
+
+        // Every occurrence stays with the staff member of the first one, their
+        // calendar holds the event of the whole series
+        let occurrence = CreateAppointment {
+            service_id: first_appt.service_id,
+            business_id: first_appt.business_id,
+            customer_name: first_appt.customer_name.clone(),
+            customer_email: first_appt.customer_email.clone(),
+            customer_phone: first_appt.customer_phone.clone(),
+            notes: first_appt.notes.clone(),
+            appointment_start_time: start_time,
+            staff_id: match &series_staff {
+                Some(staff) => staff.as_ref().map(|staff| staff.id),
+                None => first_appt.staff_id,
+            },
+            hold_token: None,
+        };
+
+        // Each occurrence gets a savepoint, a failed one leaves the others be.
+        // Should that fail, dropping the transaction rolls it back.
+        let mut occurrence_tx = match (&mut *tx).begin().await {
+            Ok(occurrence_tx) => occurrence_tx,
+            Err(e) => return internal_server_error_response(e.to_string()),
+        };
+
+        match book_occurrence(
+            &mut occurrence_tx,
+            &service,
+            &occurrence,
+            &lane_busy,
+            (series.id, &holds),
+        )
+        .await
+        {
+            Ok((appointment, staff)) => {
+                if let Err(e) = occurrence_tx.commit().await {
+                    tx.rollback().await.ok();
+                    return internal_server_error_response(e.to_string());
+                }
+
+                if series_staff.is_none() {
+                    series_staff = Some(staff);
+                }
+
+                appointments.push(appointment);
+            }
+
+            Err(error) => {
+                occurrence_tx.rollback().await.ok();
+
+                failed_occurrences.push(FailedOccurrence {
+                    local_start_time,
+                    appointment_start_time: Some(start_time),
+                    reason: error.message,
+                });
+                skipped_local_starts.push(local_start);
+            }
+        }
+    }
+
+    let (Some(first_booked), Some(last_booked), Some(staff)) =
+        (appointments.first(), appointments.last(), series_staff)
+    else {
+        tx.rollback().await.ok();
+
+        return HttpResponse::Conflict().json(ApiResponse {
+            success: false,
+            data: Some(failed_occurrences),
+            message: Some("None of the occurrences could be booked.".to_string()),
+        });
+    };
+
+    // One recurring event in the business time zone, so Google keeps the
+    // wall-clock time across DST changes like the occurrences do
+    let first_booked_local =
+        match convert_to_local_primitive(first_booked.appointment_start_time, &time_zone) {
+            Ok(local) => local,
+            Err(e) => {
+                tx.rollback().await.ok();
+                return internal_server_error_response(e);
+            }
+        };
+
+    skipped_local_starts.retain(|skipped| *skipped > first_booked_local);
+
+    let (Ok(start_fmt), Ok(end_fmt)) = (
+        first_booked_local.format(&local_format),
+        (first_booked_local + duration).format(&local_format),
+    ) else {
+        tx.rollback().await.ok();
+        return internal_server_error_response("Failed to format dates.".to_string());
+    };
+
+    let event_calendar = busy_calendar(staff.as_ref());
+
+    let notes_str = first_appt
+        .notes
+        .as_deref()
+        .map(|n| format!("\n\nNotes: {}", n))
+        .unwrap_or("N/A".to_string());
+
+    let event = GoogleCalendarEvent {
+        summary: format!(
+            "Recurring Appointment: {} for {}",
+            service.service_name, first_appt.customer_name
+        ),
+        description: format!(
+            "Service: {}\nStaff: {}\nCustomer Phone: {}\nCustomer Email: {}\nNote: {}",
+            service.service_name,
+            staff.as_ref().map_or("N/A", |staff| staff.name.as_str()),
+            first_appt.customer_phone.as_deref().unwrap_or("N/A"),
+            first_appt.customer_email.as_deref().unwrap_or("N/A"),
+            notes_str
+        ),
+        start: GoogleEventDateTime {
+            date_time: start_fmt,
+            time_zone: time_zone.clone(),
+        },
+        end: GoogleEventDateTime {
+            date_time: end_fmt,
+            time_zone: time_zone.clone(),
+        },
+        attendees: vec![GoogleEventAttendee {
+            email: first_appt.customer_email.clone().unwrap_or_default(),
+        }],
+        extended_properties: GoogleEventExtendedProperties {
+            private: HashMap::from([("series_id".to_string(), series.id.to_string())]),
+        },
+        recurrence: series_recurrence(
+            interval_weeks,
+            last_booked.appointment_start_time,
+            &skipped_local_starts,
+            &time_zone,
+        ),
+    };
+
+    let created_event =
+        match create_calendar_event(&http_client, &access_token, event_calendar, &event).await {
+            Ok(created) => created,
+
+            Err(e) => {
+                tx.rollback().await.ok();
+                return internal_server_error_response(e);
+            }
+        };
+
+    let calendar_id = created_event
+        .organizer
+        .and_then(|o| o.email)
+        .unwrap_or(event_calendar.to_string());
+
+    series = match sqlx::query_as!(
+        AppointmentSeries,
+        r#"
+        UPDATE appointment_series SET
+            google_event_id = $1,
+            google_calendar_id = $2,
+            updated_at = NOW()
+        WHERE id = $3
+        RETURNING *
+        "#,
+        created_event.id,
+        calendar_id,
+        series.id
+    )
+    .fetch_one(&mut *tx)
+    .await
+    {
+        Ok(series) => series,
+
+        Err(e) => {
+            tx.rollback().await.ok();
+            return internal_server_error_response(e.to_string());
+        }
+    };
+
+    // Each occurrence points at its own instance, so cancelling or moving one
+    // works like it does for any other booking
+    let mut synced = Vec::with_capacity(appointments.len());
+
+    for appointment in appointments {
+        match sqlx::query_as!(
+            Appointment,
+            r#"
+            UPDATE appointments SET
+                google_event_id = $1,
+                google_calendar_id = $2
+            WHERE id = $3
+            RETURNING *
+            "#,
+            recurring_instance_id(&created_event.id, appointment.appointment_start_time),
+            calendar_id,
+            appointment.id
+        )
+        .fetch_one(&mut *tx)
+        .await
+        {
+            Ok(appt) => synced.push(appt),
+
+            Err(e) => {
+                tx.rollback().await.ok();
+                return internal_server_error_response(e.to_string());
+            }
+        }
+    }
+
+    if let Err(e) = tx.commit().await {
+        return internal_server_error_response(format!("Failed to commit transaction: {}", e));
     }
+
+    let booked_starts: Vec<OffsetDateTime> = synced
+        .iter()
+        .map(|appt| appt.appointment_start_time)
+        .collect();
+
+    invalidate_booked_slot_cache(&redis_pool, &pool, first_appt.business_id, &booked_starts).await;
+
+    let message = match failed_occurrences.len() {
+        0 => "Appointment series created and synced.".to_string(),
+        failed => format!(
+            "{} of {} occurrences booked.",
+            synced.len(),
+            synced.len() + failed
+        ),
+    };
+
+    HttpResponse::Created().json(ApiResponse {
+        success: true,
+        data: Some(AppointmentSeriesResult {
+            series,
+            appointments: synced,
+            failed_occurrences,
+        }),
+        message: Some(message),
+    })
 }
 
 /* -------------------------------------------------------------------------- */
@@ -404,28 +982,2179 @@ pub async fn get_appointment_by_id(
 /* -------------------------------------------------------------------------- */
 
 #[utoipa::path(
-    get,
-    path = "/appointments",
+    post,
+    path = "/appointments/holds",
     tag = "Appointments",
+    request_body = CreateSlotHold,
     responses(
-        (status = 200, body = ApiResponse<Vec<Appointment>>),
+        (status = 201, body = ApiResponse<SlotHold>),
         (status = 400, description = "Bad Request"),
+        (status = 409, description = "Slot Already Taken Or Held"),
+        (status = 417, description = "Google Calendar Not Connected"),
+        (status = 429, description = "Too Many Holds"),
         (status = 500, description = "Internal Server Error")
     )
 )]
-pub async fn get_all_appointments(pool: web::Data<PgPool>) -> impl Responder {
-    match sqlx::query_as!(Appointment, r#"SELECT * FROM appointments"#)
-        .fetch_all(pool.get_ref())
-        .await
-    {
-        Ok(appointment) => HttpResponse::Ok().json(ApiResponse {
-            success: true,
-            data: Some(appointment),
-            message: Some("Appointments retrieved successfully".to_string()),
-        }),
+pub async fn create_slot_hold(
+    req: HttpRequest,
+    config: web::Data<Config>,
+    pool: web::Data<PgPool>,
+    body: web::Json<CreateSlotHold>,
+    http_client: web::Data<reqwest::Client>,
+    redis_pool: web::Data<deadpool_redis::Pool>,
+) -> impl Responder {
+    let new_hold = body.into_inner();
 
-        Err(e) => internal_server_error_response(e.to_string()),
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            return internal_server_error_response(format!("Failed to start transaction: {}", e));
+        }
+    };
+
+    let (service, auth_record) =
+        match load_booking_target(&mut tx, new_hold.business_id, new_hold.service_id).await {
+            Ok(target) => target,
+
+            Err(response) => {
+                tx.rollback().await.ok();
+                return response;
+            }
+        };
+
+    let holds = match lock_business_holds(&mut tx, &redis_pool, new_hold.business_id, None).await {
+        Ok(holds) => holds,
+
+        Err(response) => {
+            tx.rollback().await.ok();
+            return response;
+        }
+    };
+
+    let forwarded_for = req
+        .headers()
+        .get("X-Forwarded-For")
+        .and_then(|value| value.to_str().ok());
+
+    let client_ip = client_ip(
+        req.peer_addr().map(|addr| addr.ip()),
+        forwarded_for,
+        &config.trusted_proxies,
+    );
+
+    if let Err(message) = check_hold_limit(&holds, client_ip.as_deref(), MAX_HOLDS_PER_BUSINESS) {
+        tx.rollback().await.ok();
+
+        return HttpResponse::TooManyRequests().json(ApiResponse::<()> {
+            success: false,
+            data: None,
+            message: Some(message),
+        });
     }
+
+    let start_time = new_hold.appointment_start_time;
+    let end_time = start_time + Duration::minutes(service.duration_minutes.unwrap_or(30) as i64);
+
+    // A hold is only handed out for a slot the booking itself would get
+    let (candidates, lane_count) = match check_booking_lanes(
+        &mut tx,
+        new_hold.business_id,
+        &service,
+        new_hold.staff_id,
+        (start_time, end_time),
+        &holds,
+    )
+    .await
+    {
+        Ok(checked) => checked,
+
+        Err(error) => {
+            tx.rollback().await.ok();
+            return error.error_response();
+        }
+    };
+
+    let Some(refresh_token) = auth_record.refresh_token else {
+        tx.rollback().await.ok();
+
+        let message = format!(
+            "Info: Business {} has no Google Calendar connected.",
+            new_hold.business_id
+        );
+
+        return expectation_failed_response(message);
+    };
+
+    let access_token = match get_new_access_token(config, &http_client, refresh_token).await {
+        Ok(token) => token,
+
+        Err(e) => {
+            tx.rollback().await.ok();
+
+            return internal_server_error_response(format!(
+                "Failed to refresh Google token: {}",
+                e
+            ));
+        }
+    };
+
+    let (blocked_start, blocked_end) = buffered_window(&service, start_time, end_time);
+
+    // The hold pins the lane, redeeming it books that staff member
+    let (staff, _) = match pick_free_lane(
+        &mut tx,
+        &http_client,
+        &access_token,
+        candidates,
+        lane_count,
+        (blocked_start, blocked_end),
+    )
+    .await
+    {
+        Ok(lane) => lane,
+
+        Err(error) => {
+            tx.rollback().await.ok();
+            return error.error_response();
+        }
+    };
+
+    let hold = SlotHold {
+        hold_token: Uuid::new_v4(),
+        business_id: new_hold.business_id,
+        service_id: service.id,
+        staff_id: staff.map(|staff| staff.id),
+        start_time,
+        end_time,
+        blocked_start,
+        blocked_end,
+        expires_at: OffsetDateTime::now_utc() + Duration::minutes(HOLD_TTL_MINUTES),
+        client_ip,
+    };
+
+    // Saved while the business lock is held, so the next booking or hold sees it
+    if let Err(e) = save_hold(&redis_pool, &hold).await {
+        tx.rollback().await.ok();
+        return internal_server_error_response(e);
+    }
+
+    // Nothing was written, the transaction only served the lock
+    tx.rollback().await.ok();
+
+    HttpResponse::Created().json(ApiResponse {
+        success: true,
+        data: Some(hold),
+        message: Some(format!("Slot held for {} minutes.", HOLD_TTL_MINUTES)),
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                     -                                      */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                     -                                      */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                     -                                      */
+/* -------------------------------------------------------------------------- */
+
+#[utoipa::path(
+    get,
+    path = "/appointments/{id}",
+    tag = "Appointments",
+    params(
+        ("id" = Uuid, Path, description = "Appointment ID")
+    ),
+    responses(
+        (status = 200, body = ApiResponse<Appointment>),
+        (status = 404, description = "Not Found"),
+        (status = 400, description = "Bad Request"),
+        (status = 500, description = "Internal Server Error")
+    )
+)]
+pub async fn get_appointment_by_id(
+    path: web::Path<Uuid>,
+    pool: web::Data<PgPool>,
+) -> impl Responder {
+    let appt_id = path.into_inner();
+
+    match sqlx::query_as!(
+        Appointment,
+        r#"SELECT * FROM appointments WHERE id = $1"#,
+        appt_id
+    )
+    .fetch_one(pool.get_ref())
+    .await
+    {
+        Ok(appointment) => {
+            let response = ApiResponse {
+                success: true,
+                data: Some(appointment),
+                message: None,
+            };
+            HttpResponse::Ok().json(response)
+        }
+        Err(sqlx::Error::RowNotFound) => not_found_response("Appointment not found".to_string()),
+        Err(e) => internal_server_error_response(e.to_string()),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+#[utoipa::path(
+    get,
+    path = "/appointments",
+    tag = "Appointments",
+    params(AppointmentQuery),
+    responses(
+        (status = 200, body = ApiResponse<Vec<Appointment>>),
+        (status = 400, description = "Bad Request"),
+        (status = 500, description = "Internal Server Error")
+    )
+)]
+pub async fn get_all_appointments(
+    query: web::Query<AppointmentQuery>,
+    pool: web::Data<PgPool>,
+) -> impl Responder {
+    let status = query.status.map(|s| s.as_str());
+
+    match sqlx::query_as!(
+        Appointment,
+        r#"SELECT * FROM appointments WHERE ($1::VARCHAR IS NULL OR status = $1)"#,
+        status
+    )
+    .fetch_all(pool.get_ref())
+    .await
+    {
+        Ok(appointment) => HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(appointment),
+            message: Some("Appointments retrieved successfully".to_string()),
+        }),
+
+        Err(e) => internal_server_error_response(e.to_string()),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+#[utoipa::path(
+    post,
+    path = "/appointments/{id}/cancel",
+    tag = "Appointments",
+    params(
+        ("id" = Uuid, Path, description = "Appointment ID")
+    ),
+    request_body = CancelAppointment,
+    responses(
+        (status = 200, body = ApiResponse<Appointment>),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 409, description = "Invalid Status Transition"),
+        (status = 500, description = "Internal Server Error")
+    )
+)]
+pub async fn cancel_appointment(
+    path: web::Path<Uuid>,
+    user: Option<AuthenticatedUser>,
+    config: web::Data<Config>,
+    pool: web::Data<PgPool>,
+    body: web::Json<CancelAppointment>,
+    http_client: web::Data<reqwest::Client>,
+    (redis_pool, notifier): (web::Data<deadpool_redis::Pool>, web::Data<dyn Notifier>),
+) -> impl Responder {
+    let appt_id = path.into_inner();
+    let cancel_request = body.into_inner();
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            return internal_server_error_response(format!("Failed to start transaction: {}", e));
+        }
+    };
+
+    // Lock the row so a concurrent cancel cannot run twice
+    let appointment = match sqlx::query_as!(
+        Appointment,
+        r#"SELECT * FROM appointments WHERE id = $1 FOR UPDATE"#,
+        appt_id
+    )
+    .fetch_one(&mut *tx)
+    .await
+    {
+        Ok(appt) => appt,
+
+        Err(sqlx::Error::RowNotFound) => {
+            tx.rollback().await.ok();
+            return not_found_response("Appointment not found".to_string());
+        }
+
+        Err(e) => {
+            tx.rollback().await.ok();
+            return internal_server_error_response(e.to_string());
+        }
+    };
+
+    let cancelled_by = match appointment_actor(
+        user.as_ref(),
+        cancel_request.customer_email.as_deref(),
+        &appointment,
+    ) {
+        Some(actor) => actor,
+
+        None => {
+            tx.rollback().await.ok();
+
+            return HttpResponse::Forbidden().json(ApiResponse::<()> {
+                success: false,
+                data: None,
+                message: Some("You do not have permission to cancel this appointment.".to_string()),
+            });
+        }
+    };
+
+    if let Err(response) = check_status_transition(&appointment, AppointmentStatus::Cancelled) {
+        tx.rollback().await.ok();
+        return response;
+    }
+
+    // The later occurrences of a series go along when asked for
+    let following = match (cancel_request.scope, appointment.series_id) {
+        (CancelScope::This, _) => Vec::new(),
+
+        (CancelScope::Following, Some(series_id)) => match sqlx::query_as!(
+            Appointment,
+            r#"
+            SELECT * FROM appointments
+            WHERE series_id = $1
+            AND appointment_start_time > $2
+            AND status IN ('pending', 'confirmed')
+            ORDER BY appointment_start_time
+            FOR UPDATE
+            "#,
+            series_id,
+            appointment.appointment_start_time
+        )
+        .fetch_all(&mut *tx)
+        .await
+        {
+            Ok(following) => following,
+
+            Err(e) => {
+                tx.rollback().await.ok();
+                return internal_server_error_response(e.to_string());
+            }
+        },
+
+        (CancelScope::Following, None) => {
+            tx.rollback().await.ok();
+            return bad_request_response("This appointment is not part of a series.".to_string());
+        }
+    };
+
+    let reason = cancel_request
+        .reason
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty());
+
+    let cancelled = match sqlx::query_as!(
+        Appointment,
+        r#"
+        UPDATE appointments SET
+            status = $1,
+            cancelled_at = NOW(),
+            cancellation_reason = $2,
+            cancelled_by = $3,
+            updated_at = NOW()
+        WHERE id = $4
+        RETURNING *
+        "#,
+        AppointmentStatus::Cancelled.as_str(),
+        reason,
+        cancelled_by,
+        appt_id
+    )
+    .fetch_one(&mut *tx)
+    .await
+    {
+        Ok(appt) => appt,
+
+        Err(e) => {
+            tx.rollback().await.ok();
+            return internal_server_error_response(e.to_string());
+        }
+    };
+
+    if !following.is_empty() {
+        let following_ids: Vec<Uuid> = following.iter().map(|appt| appt.id).collect();
+
+        if let Err(e) = sqlx::query!(
+            r#"
+            UPDATE appointments SET
+                status = $1,
+                cancelled_at = NOW(),
+                cancellation_reason = $2,
+                cancelled_by = $3,
+                updated_at = NOW()
+            WHERE id = ANY($4)
+            "#,
+            AppointmentStatus::Cancelled.as_str(),
+            reason,
+            cancelled_by,
+            &following_ids
+        )
+        .execute(&mut *tx)
+        .await
+        {
+            tx.rollback().await.ok();
+            return internal_server_error_response(e.to_string());
+        }
+    }
+
+    // Remove the Google Calendar event, if one was created for this booking.
+    // Cancelling the rest of a series ends its recurring event instead, one
+    // change that is undone should the cancellation not be committed.
+    let access_token = match business_access_token(
+        &mut tx,
+        &config,
+        &http_client,
+        appointment.business_id,
+    )
+    .await
+    {
+        Ok(token) => token,
+
+        Err(response) => {
+            tx.rollback().await.ok();
+            return response;
+        }
+    };
+
+    let mut ended_series_event = None;
+
+    if let Some(access_token) = access_token.as_deref() {
+        let synced = match (cancel_request.scope, appointment.series_id) {
+            (CancelScope::Following, Some(series_id)) => {
+                end_series_event(&mut tx, &http_client, access_token, series_id, &appointment)
+                    .await
+                    .map(|ended| ended_series_event = ended)
+            }
+
+            _ => remove_appointment_event(&http_client, access_token, &appointment).await,
+        };
+
+        if let Err(e) = synced {
+            tx.rollback().await.ok();
+            return internal_server_error_response(e);
+        }
+    }
+
+    if let Err(e) = tx.commit().await {
+        if let (Some(access_token), Some((calendar_id, event_id, recurrence))) =
+            (access_token.as_deref(), ended_series_event)
+        {
+            patch_calendar_event_recurrence(
+                &http_client,
+                access_token,
+                &calendar_id,
+                &event_id,
+                &recurrence,
+            )
+            .await
+            .ok();
+        }
+
+        return internal_server_error_response(format!("Failed to commit transaction: {}", e));
+    }
+
+    let freed_starts: Vec<OffsetDateTime> = std::iter::once(&cancelled)
+        .chain(following.iter())
+        .map(|appt| appt.appointment_start_time)
+        .collect();
+
+    // Free the slots again
+    invalidate_booked_slot_cache(&redis_pool, &pool, cancelled.business_id, &freed_starts).await;
+
+    notify_waitlist(
+        &pool,
+        notifier.get_ref(),
+        cancelled.business_id,
+        cancelled.service_id,
+        &freed_starts,
+    )
+    .await;
+
+    let message = match following.len() {
+        0 => "Appointment cancelled.".to_string(),
+        later => format!("Appointment and {} later occurrences cancelled.", later),
+    };
+
+    let response = ApiResponse {
+        success: true,
+        data: Some(cancelled),
+        message: Some(message),
+    };
+
+    HttpResponse::Ok().json(response)
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+#[utoipa::path(
+    patch,
+    path = "/appointments/{id}",
+    tag = "Appointments",
+    params(
+        ("id" = Uuid, Path, description = "Appointment ID")
+    ),
+    request_body = RescheduleAppointment,
+    responses(
+        (status = 200, body = ApiResponse<Appointment>),
+        (status = 400, description = "Bad Request"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 409, description = "Appointment No Longer Active"),
+        (status = 500, description = "Internal Server Error")
+    )
+)]
+pub async fn reschedule_appointment(
+    path: web::Path<Uuid>,
+    user: Option<AuthenticatedUser>,
+    config: web::Data<Config>,
+    pool: web::Data<PgPool>,
+    body: web::Json<RescheduleAppointment>,
+    http_client: web::Data<reqwest::Client>,
+    (redis_pool, notifier): (web::Data<deadpool_redis::Pool>, web::Data<dyn Notifier>),
+) -> impl Responder {
+    let appt_id = path.into_inner();
+    let reschedule_request = body.into_inner();
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            return internal_server_error_response(format!("Failed to start transaction: {}", e));
+        }
+    };
+
+    let appointment = match sqlx::query_as!(
+        Appointment,
+        r#"SELECT * FROM appointments WHERE id = $1 FOR UPDATE"#,
+        appt_id
+    )
+    .fetch_one(&mut *tx)
+    .await
+    {
+        Ok(appt) => appt,
+
+        Err(sqlx::Error::RowNotFound) => {
+            tx.rollback().await.ok();
+            return not_found_response("Appointment not found".to_string());
+        }
+
+        Err(e) => {
+            tx.rollback().await.ok();
+            return internal_server_error_response(e.to_string());
+        }
+    };
+
+    let actor = appointment_actor(
+        user.as_ref(),
+        reschedule_request.customer_email.as_deref(),
+        &appointment,
+    );
+
+    if actor.is_none() {
+        tx.rollback().await.ok();
+
+        return HttpResponse::Forbidden().json(ApiResponse::<()> {
+            success: false,
+            data: None,
+            message: Some("You do not have permission to reschedule this appointment.".to_string()),
+        });
+    }
+
+    match appointment_status(&appointment) {
+        Ok(status) if status.is_active() => {}
+
+        Ok(status) => {
+            tx.rollback().await.ok();
+
+            return conflict_reponse(format!(
+                "Cannot reschedule a {} appointment.",
+                status.as_str()
+            ));
+        }
+
+        Err(response) => {
+            tx.rollback().await.ok();
+            return response;
+        }
+    }
+
+    // Recompute the end time from the service duration
+    let service = match sqlx::query_as!(
+        Service,
+        r#"SELECT * FROM services WHERE id = $1"#,
+        appointment.service_id
+    )
+    .fetch_one(&mut *tx)
+    .await
+    {
+        Ok(s) => s,
+
+        Err(e) => {
+            tx.rollback().await.ok();
+            return internal_server_error_response(e.to_string());
+        }
+    };
+
+    let start_time = reschedule_request.appointment_start_time;
+    let duration = service.duration_minutes.unwrap_or(30);
+    let end_time = start_time + Duration::minutes(duration as i64);
+    let (blocked_start, blocked_end) = buffered_window(&service, start_time, end_time);
+
+    // The business may move a booking anywhere, customers follow the usual rules
+    if actor == Some("customer")
+        && let Err(error) =
+            check_booking_window(&mut tx, appointment.business_id, &service, start_time).await
+    {
+        tx.rollback().await.ok();
+        return error.error_response();
+    }
+
+    // The booking stays with the staff member it was assigned to
+    let staff = match appointment.staff_id {
+        Some(staff_id) => {
+            match sqlx::query_as!(Staff, "SELECT * FROM staff WHERE id = $1", staff_id)
+                .fetch_one(&mut *tx)
+                .await
+            {
+                Ok(staff) => Some(staff),
+
+                Err(e) => {
+                    tx.rollback().await.ok();
+                    return internal_server_error_response(e.to_string());
+                }
+            }
+        }
+
+        None => None,
+    };
+
+    if let Err(error) = check_operating_hours(
+        &mut tx,
+        appointment.business_id,
+        service.id,
+        appointment.staff_id,
+        start_time,
+        end_time,
+    )
+    .await
+    {
+        tx.rollback().await.ok();
+        return error.error_response();
+    }
+
+    if let Err(error) =
+        check_breaks(&mut tx, appointment.business_id, blocked_start, blocked_end).await
+    {
+        tx.rollback().await.ok();
+        return error.error_response();
+    }
+
+    let holds = match lock_business_holds(&mut tx, &redis_pool, appointment.business_id, None).await
+    {
+        Ok(holds) => holds,
+
+        Err(response) => {
+            tx.rollback().await.ok();
+            return response;
+        }
+    };
+
+    let joined_session = match check_seat_is_free(
+        &mut tx,
+        appointment.business_id,
+        &service,
+        appointment.staff_id,
+        (start_time, end_time),
+        Some(appointment.id),
+        &holds,
+    )
+    .await
+    {
+        Ok(session_id) => session_id,
+
+        Err(error) => {
+            tx.rollback().await.ok();
+            return error.error_response();
+        }
+    };
+
+    if joined_session.is_none()
+        && let Err(error) = check_resources(
+            &mut tx,
+            service.id,
+            (blocked_start, blocked_end),
+            Some(appointment.id),
+            &holds,
+        )
+        .await
+    {
+        tx.rollback().await.ok();
+        return error.error_response();
+    }
+
+    let access_token = match business_access_token(
+        &mut tx,
+        &config,
+        &http_client,
+        appointment.business_id,
+    )
+    .await
+    {
+        Ok(token) => token,
+
+        Err(response) => {
+            tx.rollback().await.ok();
+            return response;
+        }
+    };
+
+    // The booking's own event shows up as busy at its old time, a running
+    // session at the new one
+    if joined_session.is_none()
+        && let Some(access_token) = access_token.as_deref()
+    {
+        let mut ignored_periods =
+            match shared_calendar_bookings(&mut tx, staff.as_ref(), (blocked_start, blocked_end))
+                .await
+            {
+                Ok(bookings) => bookings,
+
+                Err(e) => {
+                    tx.rollback().await.ok();
+                    return internal_server_error_response(e.to_string());
+                }
+            };
+
+        ignored_periods.push((
+            appointment.appointment_start_time,
+            appointment.appointment_end_time,
+        ));
+
+        if let Err(error) = check_calendar_is_free(
+            &http_client,
+            access_token,
+            busy_calendar(staff.as_ref()),
+            blocked_start,
+            blocked_end,
+            &ignored_periods,
+        )
+        .await
+        {
+            tx.rollback().await.ok();
+            return error.error_response();
+        }
+    }
+
+    // A moved seat joins the session at its new time or opens one there
+    let session_id = joined_session.or((service.capacity > 1).then(Uuid::new_v4));
+
+    let mut rescheduled = match sqlx::query_as!(
+        Appointment,
+        r#"
+        UPDATE appointments SET
+            appointment_start_time = $1,
+            appointment_end_time = $2,
+            session_id = $3,
+            updated_at = NOW()
+        WHERE id = $4
+        RETURNING *
+        "#,
+        start_time,
+        end_time,
+        session_id,
+        appt_id
+    )
+    .fetch_one(&mut *tx)
+    .await
+    {
+        Ok(appt) => appt,
+
+        Err(e) => {
+            tx.rollback().await.ok();
+            return booking_write_error(e).error_response();
+        }
+    };
+
+    let event_ref = match access_token.as_deref() {
+        Some(access_token) => {
+            match calendar_event_ref(&http_client, access_token, &appointment).await {
+                Ok(event_ref) => event_ref.map(|event_ref| (access_token, event_ref)),
+
+                Err(e) => {
+                    tx.rollback().await.ok();
+                    return internal_server_error_response(e);
+                }
+            }
+        }
+
+        None => None,
+    };
+
+    // Move the existing Google Calendar event instead of creating a new one
+    if let Some((access_token, (calendar_id, event_id))) = event_ref {
+        let (start_fmt, end_fmt) = match (start_time.format(&Rfc3339), end_time.format(&Rfc3339)) {
+            (Ok(start_fmt), Ok(end_fmt)) => (start_fmt, end_fmt),
+
+            _ => {
+                tx.rollback().await.ok();
+                return internal_server_error_response("Failed to format dates.".to_string());
+            }
+        };
+
+        let times = GoogleEventTimesPatch {
+            start: GoogleEventDateTime {
+                date_time: start_fmt,
+                time_zone: "UTC".to_string(),
+            },
+            end: GoogleEventDateTime {
+                date_time: end_fmt,
+                time_zone: "UTC".to_string(),
+            },
+        };
+
+        let patched_event = match move_calendar_event(
+            &http_client,
+            access_token,
+            (&calendar_id, &event_id),
+            &appointment,
+            &times,
+        )
+        .await
+        {
+            Ok(event) => event,
+
+            Err(response) => {
+                tx.rollback().await.ok();
+                return response;
+            }
+        };
+
+        // Bookings found through the appointment id keep the reference from now on
+        rescheduled = match sqlx::query_as!(
+            Appointment,
+            r#"
+            UPDATE appointments SET
+                google_event_id = $1,
+                google_calendar_id = $2,
+                google_event_etag = $3
+            WHERE id = $4
+            RETURNING *
+            "#,
+            event_id,
+            calendar_id,
+            patched_event.etag,
+            appt_id
+        )
+        .fetch_one(&mut *tx)
+        .await
+        {
+            Ok(appt) => appt,
+
+            Err(e) => {
+                tx.rollback().await.ok();
+                return internal_server_error_response(e.to_string());
+            }
+        };
+    }
+
+    if let Err(e) = tx.commit().await {
+        return internal_server_error_response(format!("Failed to commit transaction: {}", e));
+    }
+
+    // Both the old and the new day have changed
+    invalidate_booked_slot_cache(
+        &redis_pool,
+        &pool,
+        rescheduled.business_id,
+        &[
+            appointment.appointment_start_time,
+            rescheduled.appointment_start_time,
+        ],
+    )
+    .await;
+
+    // The old time is open again
+    if appointment.appointment_start_time != rescheduled.appointment_start_time {
+        notify_waitlist(
+            &pool,
+            notifier.get_ref(),
+            rescheduled.business_id,
+            rescheduled.service_id,
+            &[appointment.appointment_start_time],
+        )
+        .await;
+    }
+
+    let response = ApiResponse {
+        success: true,
+        data: Some(rescheduled),
+        message: Some("Appointment rescheduled and synced.".to_string()),
+    };
+
+    HttpResponse::Ok().json(response)
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+#[utoipa::path(
+    post,
+    path = "/appointments/{id}/confirm",
+    tag = "Appointments",
+    params(
+        ("id" = Uuid, Path, description = "Appointment ID")
+    ),
+    responses(
+        (status = 200, body = ApiResponse<Appointment>),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 409, description = "Invalid Status Transition"),
+        (status = 500, description = "Internal Server Error")
+    )
+)]
+pub async fn confirm_appointment(
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<deadpool_redis::Pool>,
+) -> impl Responder {
+    set_appointment_status(
+        path.into_inner(),
+        user,
+        &pool,
+        &redis_pool,
+        AppointmentStatus::Confirmed,
+    )
+    .await
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+#[utoipa::path(
+    post,
+    path = "/appointments/{id}/complete",
+    tag = "Appointments",
+    params(
+        ("id" = Uuid, Path, description = "Appointment ID")
+    ),
+    responses(
+        (status = 200, body = ApiResponse<Appointment>),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 409, description = "Invalid Status Transition"),
+        (status = 500, description = "Internal Server Error")
+    )
+)]
+pub async fn complete_appointment(
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<deadpool_redis::Pool>,
+) -> impl Responder {
+    set_appointment_status(
+        path.into_inner(),
+        user,
+        &pool,
+        &redis_pool,
+        AppointmentStatus::Completed,
+    )
+    .await
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+#[utoipa::path(
+    post,
+    path = "/appointments/{id}/no-show",
+    tag = "Appointments",
+    params(
+        ("id" = Uuid, Path, description = "Appointment ID")
+    ),
+    responses(
+        (status = 200, body = ApiResponse<Appointment>),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 409, description = "Invalid Status Transition"),
+        (status = 500, description = "Internal Server Error")
+    )
+)]
+pub async fn mark_appointment_no_show(
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<deadpool_redis::Pool>,
+) -> impl Responder {
+    set_appointment_status(
+        path.into_inner(),
+        user,
+        &pool,
+        &redis_pool,
+        AppointmentStatus::NoShow,
+    )
+    .await
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+// Business-only status change shared by confirm / complete / no-show
+async fn set_appointment_status(
+    appt_id: Uuid,
+    user: AuthenticatedUser,
+    pool: &PgPool,
+    redis_pool: &deadpool_redis::Pool,
+    next_status: AppointmentStatus,
+) -> HttpResponse {
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            return internal_server_error_response(format!("Failed to start transaction: {}", e));
+        }
+    };
+
+    let appointment = match sqlx::query_as!(
+        Appointment,
+        r#"SELECT * FROM appointments WHERE id = $1 FOR UPDATE"#,
+        appt_id
+    )
+    .fetch_one(&mut *tx)
+    .await
+    {
+        Ok(appt) => appt,
+
+        Err(sqlx::Error::RowNotFound) => {
+            tx.rollback().await.ok();
+            return not_found_response("Appointment not found".to_string());
+        }
+
+        Err(e) => {
+            tx.rollback().await.ok();
+            return internal_server_error_response(e.to_string());
+        }
+    };
+
+    if appointment.business_id != user.user_id {
+        tx.rollback().await.ok();
+
+        return HttpResponse::Forbidden().json(ApiResponse::<()> {
+            success: false,
+            data: None,
+            message: Some("You do not have permission to update this appointment.".to_string()),
+        });
+    }
+
+    if let Err(response) = check_status_transition(&appointment, next_status) {
+        tx.rollback().await.ok();
+        return response;
+    }
+
+    let updated = match sqlx::query_as!(
+        Appointment,
+        r#"
+        UPDATE appointments SET
+            status = $1,
+            updated_at = NOW()
+        WHERE id = $2
+        RETURNING *
+        "#,
+        next_status.as_str(),
+        appt_id
+    )
+    .fetch_one(&mut *tx)
+    .await
+    {
+        Ok(appt) => appt,
+
+        Err(e) => {
+            tx.rollback().await.ok();
+            return internal_server_error_response(e.to_string());
+        }
+    };
+
+    if let Err(e) = tx.commit().await {
+        return internal_server_error_response(format!("Failed to commit transaction: {}", e));
+    }
+
+    // A finished booking no longer blocks its slot
+    if !next_status.is_active() {
+        invalidate_booked_slot_cache(
+            redis_pool,
+            pool,
+            updated.business_id,
+            &[updated.appointment_start_time],
+        )
+        .await;
+    }
+
+    HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        data: Some(updated),
+        message: Some(format!("Appointment marked as {}.", next_status.as_str())),
+    })
+}
+
+fn appointment_status(appointment: &Appointment) -> Result<AppointmentStatus, HttpResponse> {
+    AppointmentStatus::from_str(&appointment.status).map_err(internal_server_error_response)
+}
+
+fn check_status_transition(
+    appointment: &Appointment,
+    next_status: AppointmentStatus,
+) -> Result<(), HttpResponse> {
+    let current = appointment_status(appointment)?;
+
+    if !current.can_transition_to(next_status) {
+        return Err(conflict_reponse(format!(
+            "Cannot change a {} appointment to {}.",
+            current.as_str(),
+            next_status.as_str()
+        )));
+    }
+
+    Ok(())
+}
+
+// The business acts with its token, the customer with the email they booked with
+fn appointment_actor(
+    user: Option<&AuthenticatedUser>,
+    customer_email: Option<&str>,
+    appointment: &Appointment,
+) -> Option<&'static str> {
+    if user.is_some_and(|u| u.user_id == appointment.business_id) {
+        return Some("business");
+    }
+
+    match (customer_email, appointment.customer_email.as_deref()) {
+        (Some(given), Some(booked)) if given.trim().eq_ignore_ascii_case(booked.trim()) => {
+            Some("customer")
+        }
+        _ => None,
+    }
+}
+
+// The service being booked and the Google credentials of its business, which
+// must be accepting appointments
+async fn load_booking_target(
+    tx: &mut Transaction<'_, Postgres>,
+    business_id: Uuid,
+    service_id: Uuid,
+) -> Result<(Service, Auth), HttpResponse> {
+    // Fetch Service to know the duration
+    let service = match sqlx::query_as!(
+        Service,
+        r#"SELECT * FROM services WHERE id = $1"#,
+        service_id
+    )
+    .fetch_one(&mut **tx)
+    .await
+    {
+        Ok(s) => s,
+
+        Err(sqlx::Error::RowNotFound) => {
+            return Err(bad_request_response("Invalid service_id.".to_string()));
+        }
+
+        Err(e) => return Err(internal_server_error_response(e.to_string())),
+    };
+
+    // Fetch Business Auth for Google Token
+    let auth_record = match sqlx::query_as!(
+        Auth,
+        r#"SELECT * FROM auth WHERE user_id = $1"#,
+        business_id
+    )
+    .fetch_one(&mut **tx)
+    .await
+    {
+        Ok(a) => a,
+
+        Err(sqlx::Error::RowNotFound) => {
+            return Err(bad_request_response(
+                "Business not found or not authenticated.".to_string(),
+            ));
+        }
+
+        Err(e) => return Err(internal_server_error_response(e.to_string())),
+    };
+
+    // Check Business Active Status
+    let is_active = sqlx::query_scalar!(
+        r#"SELECT is_active as "is_active!: bool" FROM users WHERE id = $1"#,
+        business_id
+    )
+    .fetch_one(&mut **tx)
+    .await
+    .unwrap_or(false);
+
+    if !is_active {
+        return Err(bad_request_response(
+            "This business is not currently accepting appointments.".to_string(),
+        ));
+    }
+
+    Ok((service, auth_record))
+}
+
+// A lane able to take a booking, and the group session it would join
+type LaneCandidate = (Option<Staff>, Option<Uuid>);
+
+// Google busy time of each lane, keyed like `Appointment::staff_id`
+type LaneBusyPeriods = HashMap<Option<Uuid>, Vec<(OffsetDateTime, OffsetDateTime)>>;
+
+// Everything a new booking must pass before Google is asked: the booking
+// window, breaks, the hours and bookings of each lane, and the resources.
+// The lanes that can take it come back best first, next to how many were tried.
+async fn check_booking_lanes(
+    tx: &mut Transaction<'_, Postgres>,
+    business_id: Uuid,
+    service: &Service,
+    staff_id: Option<Uuid>,
+    (start_time, end_time): (OffsetDateTime, OffsetDateTime),
+    holds: &[SlotHold],
+) -> Result<(Vec<LaneCandidate>, usize), BookingError> {
+    let (blocked_start, blocked_end) = buffered_window(service, start_time, end_time);
+
+    check_booking_window(tx, business_id, service, start_time).await?;
+
+    // Breaks close the business for every staff member alike
+    check_breaks(tx, business_id, blocked_start, blocked_end).await?;
+
+    let lanes = booking_lanes(tx, business_id, service.id)
+        .await
+        .map_err(|e| BookingError::internal(e.to_string()))?;
+
+    let lanes: Vec<Option<Staff>> = match staff_id {
+        Some(staff_id) => lanes
+            .into_iter()
+            .filter(|lane| lane.as_ref().is_some_and(|staff| staff.id == staff_id))
+            .collect(),
+
+        None => lanes,
+    };
+
+    if lanes.is_empty() {
+        return Err(BookingError::bad_request(match staff_id {
+            Some(_) => "This staff member does not offer the service.".to_string(),
+            None => "No staff member offers this service.".to_string(),
+        }));
+    }
+
+    let lane_count = lanes.len();
+    let mut last_error = None;
+    let mut candidates = Vec::new();
+
+    for lane in lanes {
+        let staff_id = lane.as_ref().map(|staff| staff.id);
+
+        let checked = match check_operating_hours(
+            tx,
+            business_id,
+            service.id,
+            staff_id,
+            start_time,
+            end_time,
+        )
+        .await
+        {
+            Ok(()) => {
+                check_seat_is_free(
+                    tx,
+                    business_id,
+                    service,
+                    staff_id,
+                    (start_time, end_time),
+                    None,
+                    holds,
+                )
+                .await
+            }
+
+            Err(response) => Err(response),
+        };
+
+        match checked {
+            Ok(session_id) => candidates.push((lane, session_id)),
+            Err(response) => last_error = Some(response),
+        }
+    }
+
+    if candidates.is_empty() {
+        return Err(no_free_lane_error(lane_count, last_error));
+    }
+
+    order_lane_candidates(&mut candidates);
+
+    // Checked under the business lock taken above, like the lanes themselves.
+    // A seat in a running session uses what the session already holds.
+    // Seats held in the class the booking would open share what it reserves
+    let other_holds: Vec<SlotHold> = holds
+        .iter()
+        .filter(|hold| {
+            service.capacity == 1
+                || hold.service_id != service.id
+                || (hold.start_time, hold.end_time) != (start_time, end_time)
+        })
+        .cloned()
+        .collect();
+
+    if let Err(error) = check_resources(
+        tx,
+        service.id,
+        (blocked_start, blocked_end),
+        None,
+        &other_holds,
+    )
+    .await
+    {
+        candidates.retain(|(_, session_id)| session_id.is_some());
+
+        if candidates.is_empty() {
+            return Err(error);
+        }
+    }
+
+    Ok((candidates, lane_count))
+}
+
+// The cached slot list may predate personal events the owner added since,
+// the first lane that is still free takes the booking
+async fn pick_free_lane(
+    tx: &mut Transaction<'_, Postgres>,
+    http_client: &reqwest::Client,
+    access_token: &str,
+    candidates: Vec<LaneCandidate>,
+    lane_count: usize,
+    (blocked_start, blocked_end): (OffsetDateTime, OffsetDateTime),
+) -> Result<LaneCandidate, BookingError> {
+    let mut last_error = None;
+
+    // A running session is on the calendar already, through its own events
+    for (lane, session_id) in candidates {
+        if session_id.is_some() {
+            return Ok((lane, session_id));
+        }
+
+        let shared_bookings =
+            shared_calendar_bookings(tx, lane.as_ref(), (blocked_start, blocked_end))
+                .await
+                .map_err(|e| BookingError::internal(e.to_string()))?;
+
+        if let Err(error) = check_calendar_is_free(
+            http_client,
+            access_token,
+            busy_calendar(lane.as_ref()),
+            blocked_start,
+            blocked_end,
+            &shared_bookings,
+        )
+        .await
+        {
+            last_error = Some(error);
+            continue;
+        }
+
+        return Ok((lane, session_id));
+    }
+
+    Err(no_free_lane_error(lane_count, last_error))
+}
+
+// Google busy time of every lane over a whole window, bookings of the other
+// calendar-less staff already cut out. A series fetches it before taking the
+// business lock rather than once per occurrence under it.
+async fn fetch_lane_busy_periods(
+    tx: &mut Transaction<'_, Postgres>,
+    http_client: &reqwest::Client,
+    access_token: &str,
+    lanes: &[Option<Staff>],
+    (window_start, window_end): (OffsetDateTime, OffsetDateTime),
+) -> Result<LaneBusyPeriods, HttpResponse> {
+    let mut lane_busy = HashMap::new();
+
+    for lane in lanes {
+        let shared_bookings =
+            shared_calendar_bookings(tx, lane.as_ref(), (window_start, window_end))
+                .await
+                .map_err(|e| internal_server_error_response(e.to_string()))?;
+
+        // freeBusy turns down overly long ranges, a year goes in a few pieces
+        let mut busy_periods = Vec::new();
+        let mut chunk_start = window_start;
+
+        while chunk_start < window_end {
+            let chunk_end = (chunk_start + Duration::days(FREE_BUSY_CHUNK_DAYS)).min(window_end);
+
+            let chunk = fetch_busy_periods(
+                http_client,
+                access_token,
+                busy_calendar(lane.as_ref()),
+                chunk_start,
+                chunk_end,
+            )
+            .await
+            .map_err(internal_server_error_response)?;
+
+            busy_periods.extend(
+                chunk
+                    .into_iter()
+                    .flat_map(|period| subtract_periods(period, &shared_bookings)),
+            );
+            chunk_start = chunk_end;
+        }
+
+        lane_busy.insert(lane.as_ref().map(|staff| staff.id), busy_periods);
+    }
+
+    Ok(lane_busy)
+}
+
+// Like `pick_free_lane`, against busy time fetched up front
+fn pick_prefetched_lane(
+    candidates: Vec<LaneCandidate>,
+    lane_count: usize,
+    lane_busy: &LaneBusyPeriods,
+    blocked_window: (OffsetDateTime, OffsetDateTime),
+) -> Result<LaneCandidate, BookingError> {
+    let mut last_error = None;
+
+    for (lane, session_id) in candidates {
+        let checked = match (
+            session_id,
+            lane_busy.get(&lane.as_ref().map(|staff| staff.id)),
+        ) {
+            (Some(_), _) => Ok(()),
+            (None, Some(busy_periods)) => check_busy_periods(busy_periods, &[], blocked_window),
+
+            // A lane that showed up since has nothing fetched to go by
+            (None, None) => Err(business_unavailable_error()),
+        };
+
+        match checked {
+            Ok(()) => return Ok((lane, session_id)),
+            Err(error) => last_error = Some(error),
+        }
+    }
+
+    Err(no_free_lane_error(lane_count, last_error))
+}
+
+// One occurrence of a series goes through the same checks as a single booking
+async fn book_occurrence(
+    tx: &mut Transaction<'_, Postgres>,
+    service: &Service,
+    occurrence: &CreateAppointment,
+    lane_busy: &LaneBusyPeriods,
+    (series_id, holds): (Uuid, &[SlotHold]),
+) -> Result<(Appointment, Option<Staff>), BookingError> {
+    let start_time = occurrence.appointment_start_time;
+    let end_time = start_time + Duration::minutes(service.duration_minutes.unwrap_or(30) as i64);
+
+    let (candidates, lane_count) = check_booking_lanes(
+        tx,
+        occurrence.business_id,
+        service,
+        occurrence.staff_id,
+        (start_time, end_time),
+        holds,
+    )
+    .await?;
+
+    let (staff, joined_session) = pick_prefetched_lane(
+        candidates,
+        lane_count,
+        lane_busy,
+        buffered_window(service, start_time, end_time),
+    )?;
+
+    let session_id = joined_session.or((service.capacity > 1).then(Uuid::new_v4));
+
+    let appointment = insert_appointment(
+        tx,
+        occurrence,
+        end_time,
+        staff.as_ref().map(|staff| staff.id),
+        session_id,
+        Some(series_id),
+    )
+    .await?;
+
+    Ok((appointment, staff))
+}
+
+// Writes the booking with every resource its service needs
+async fn insert_appointment(
+    tx: &mut Transaction<'_, Postgres>,
+    new_appt: &CreateAppointment,
+    end_time: OffsetDateTime,
+    staff_id: Option<Uuid>,
+    session_id: Option<Uuid>,
+    series_id: Option<Uuid>,
+) -> Result<Appointment, BookingError> {
+    let appointment = sqlx::query_as!(
+        Appointment,
+        r#"
+        INSERT INTO appointments (
+            service_id, business_id, customer_name, customer_email, 
+            customer_phone, appointment_start_time, notes, appointment_end_time,
+            staff_id, session_id, series_id
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        RETURNING *
+        "#,
+        new_appt.service_id,
+        new_appt.business_id,
+        new_appt.customer_name,
+        new_appt.customer_email,
+        new_appt.customer_phone,
+        new_appt.appointment_start_time,
+        new_appt.notes,
+        end_time,
+        staff_id,
+        session_id,
+        series_id
+    )
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(booking_write_error)?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO appointment_resources (appointment_id, resource_id)
+        SELECT $1, resource_id FROM service_resources WHERE service_id = $2
+        "#,
+        appointment.id,
+        new_appt.service_id
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| BookingError::internal(e.to_string()))?;
+
+    Ok(appointment)
+}
+
+// Rejects starts that are too soon or too far ahead for the service
+async fn check_booking_window(
+    tx: &mut Transaction<'_, Postgres>,
+    business_id: Uuid,
+    service: &Service,
+    start_time: OffsetDateTime,
+) -> Result<(), BookingError> {
+    let business = sqlx::query!(
+        "SELECT min_notice_minutes, max_advance_days FROM users WHERE id = $1",
+        business_id
+    )
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| BookingError::internal(e.to_string()))?;
+
+    let min_notice_minutes = service
+        .min_notice_minutes
+        .unwrap_or(business.min_notice_minutes);
+    let max_advance_days = service
+        .max_advance_days
+        .unwrap_or(business.max_advance_days);
+
+    let (earliest_start, latest_start) = booking_window(
+        OffsetDateTime::now_utc(),
+        min_notice_minutes as i64,
+        max_advance_days as i64,
+    );
+
+    if start_time < earliest_start && min_notice_minutes == 0 {
+        return Err(BookingError::bad_request(
+            "Cannot book a time in the past.".to_string(),
+        ));
+    }
+
+    if start_time < earliest_start {
+        return Err(BookingError::bad_request(format!(
+            "Bookings must be made at least {} minutes in advance.",
+            min_notice_minutes
+        )));
+    }
+
+    if start_time > latest_start {
+        return Err(BookingError::bad_request(format!(
+            "Bookings can only be made up to {} days in advance.",
+            max_advance_days
+        )));
+    }
+
+    Ok(())
+}
+
+// The requested window must fit inside one opening window, overnight ones included
+async fn check_operating_hours(
+    tx: &mut Transaction<'_, Postgres>,
+    business_id: Uuid,
+    service_id: Uuid,
+    staff_id: Option<Uuid>,
+    start_time: OffsetDateTime,
+    end_time: OffsetDateTime,
+) -> Result<(), BookingError> {
+    let time_zone = business_time_zone(tx, business_id)
+        .await
+        .map_err(|e| BookingError::internal(e.to_string()))?;
+
+    let tz = Tz::from_str(&time_zone)
+        .map_err(|_| BookingError::internal("Invalid DB Timezone".to_string()))?;
+
+    let local_start =
+        convert_to_local_primitive(start_time, &time_zone).map_err(BookingError::internal)?;
+
+    // Date overrides take precedence over the weekly rules, the service and
+    // the staff member may only be available for part of that time
+    let (opening_hours, previous_day_hours) =
+        opening_hours_for_booking(tx, business_id, service_id, staff_id, local_start.date())
+            .await
+            .map_err(|e| BookingError::internal(e.to_string()))?;
+
+    if opening_hours.is_empty() && previous_day_hours.is_empty() {
+        return Err(BookingError::bad_request(
+            "Business is closed on this day.".to_string(),
+        ));
+    }
+
+    // Overnight windows may carry the booking past midnight
+    let business_is_available = fits_opening_hours(
+        start_time,
+        end_time,
+        local_start.date(),
+        &opening_hours,
+        &previous_day_hours,
+        &tz,
+    );
+
+    if !business_is_available {
+        return Err(BookingError::bad_request(
+            "Requested slot is outside operating hours.".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+// Buffers may not run into a break either, same as with other bookings
+async fn check_breaks(
+    tx: &mut Transaction<'_, Postgres>,
+    business_id: Uuid,
+    blocked_start: OffsetDateTime,
+    blocked_end: OffsetDateTime,
+) -> Result<(), BookingError> {
+    let time_zone = business_time_zone(tx, business_id)
+        .await
+        .map_err(|e| BookingError::internal(e.to_string()))?;
+
+    let tz = Tz::from_str(&time_zone)
+        .map_err(|_| BookingError::internal("Invalid DB Timezone".to_string()))?;
+
+    let breaks = breaks_between(tx, business_id, blocked_start, blocked_end, &tz)
+        .await
+        .map_err(|e| BookingError::internal(e.to_string()))?;
+
+    if !breaks.is_empty() {
+        return Err(BookingError::bad_request(
+            "Requested slot overlaps a scheduled break.".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+// Locks the business row, then reads the holds other customers have on it.
+// Holds are only handed out under the same lock, none can slip in until commit.
+async fn lock_business_holds(
+    tx: &mut Transaction<'_, Postgres>,
+    redis_pool: &deadpool_redis::Pool,
+    business_id: Uuid,
+    redeemed_hold: Option<Uuid>,
+) -> Result<Vec<SlotHold>, HttpResponse> {
+    sqlx::query!(
+        r#"SELECT id FROM users WHERE id = $1 FOR UPDATE"#,
+        business_id
+    )
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| internal_server_error_response(e.to_string()))?;
+
+    let holds = business_holds(redis_pool, business_id)
+        .await
+        .map_err(internal_server_error_response)?;
+
+    Ok(holds
+        .into_iter()
+        .filter(|hold| Some(hold.hold_token) != redeemed_hold)
+        .collect())
+}
+
+// The window a booking really occupies, prep and cleanup included
+fn buffered_window(
+    service: &Service,
+    start_time: OffsetDateTime,
+    end_time: OffsetDateTime,
+) -> (OffsetDateTime, OffsetDateTime) {
+    (
+        start_time - Duration::minutes(service.buffer_before_minutes as i64),
+        end_time + Duration::minutes(service.buffer_after_minutes as i64),
+    )
+}
+
+// Locks the business row so concurrent bookings are serialized, then checks
+// the lane. Group classes take bookings until their seats run out: a booking
+// lining up with a session of the service joins it, anything else needs the
+// window to itself. Hands back the session joined, if any.
+async fn check_seat_is_free(
+    tx: &mut Transaction<'_, Postgres>,
+    business_id: Uuid,
+    service: &Service,
+    staff_id: Option<Uuid>,
+    (start_time, end_time): (OffsetDateTime, OffsetDateTime),
+    exclude_appointment_id: Option<Uuid>,
+    holds: &[SlotHold],
+) -> Result<Option<Uuid>, BookingError> {
+    sqlx::query!(
+        r#"SELECT id FROM users WHERE id = $1 FOR UPDATE"#,
+        business_id
+    )
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| BookingError::internal(e.to_string()))?;
+
+    let session = sqlx::query!(
+        r#"
+        SELECT session_id as "session_id!", COUNT(*) as "booked_seats!"
+        FROM appointments
+        WHERE service_id = $1
+        AND staff_id IS NOT DISTINCT FROM $2
+        AND appointment_start_time = $3
+        AND appointment_end_time = $4
+        AND status IN ('pending', 'confirmed')
+        AND session_id IS NOT NULL
+        AND ($5::UUID IS NULL OR id <> $5)
+        GROUP BY session_id
+        "#,
+        service.id,
+        staff_id,
+        start_time,
+        end_time,
+        exclude_appointment_id
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| BookingError::internal(e.to_string()))?;
+
+    let session_id = session.as_ref().map(|session| session.session_id);
+
+    check_slot_is_free(
+        tx,
+        business_id,
+        staff_id,
+        buffered_window(service, start_time, end_time),
+        exclude_appointment_id,
+        session_id,
+    )
+    .await?;
+
+    // Checkouts in progress count like bookings
+    let Some(held) = held_seats(
+        holds,
+        staff_id,
+        (service.id, service.capacity),
+        (start_time, end_time),
+        buffered_window(service, start_time, end_time),
+    ) else {
+        return Err(BookingError::conflict(
+            "This time is held by another customer.".to_string(),
+        ));
+    };
+
+    let taken_seats = session.map_or(0, |session| session.booked_seats as i32) + held;
+
+    if taken_seats > 0 && taken_seats >= service.capacity {
+        return Err(BookingError::conflict(
+            "This class is fully booked at the requested time.".to_string(),
+        ));
+    }
+
+    Ok(session_id)
+}
+
+// Makes sure no active appointment colliding with the lane, buffers included,
+// holds any part of the window. Seats of the session being joined are no obstacle.
+async fn check_slot_is_free(
+    tx: &mut Transaction<'_, Postgres>,
+    business_id: Uuid,
+    staff_id: Option<Uuid>,
+    (start_time, end_time): (OffsetDateTime, OffsetDateTime),
+    exclude_appointment_id: Option<Uuid>,
+    joined_session_id: Option<Uuid>,
+) -> Result<(), BookingError> {
+    let is_taken = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM appointments a
+            JOIN services s ON s.id = a.service_id
+            WHERE a.business_id = $1
+            AND a.status IN ('pending', 'confirmed')
+            AND a.appointment_start_time - make_interval(mins => s.buffer_before_minutes) < $3
+            AND a.appointment_end_time + make_interval(mins => s.buffer_after_minutes) > $2
+            AND ($4::UUID IS NULL OR a.id <> $4)
+            AND ($5::UUID IS NULL OR a.staff_id IS NULL OR a.staff_id = $5)
+            AND ($6::UUID IS NULL OR a.session_id IS DISTINCT FROM $6)
+        ) as "is_taken!: bool"
+        "#,
+        business_id,
+        start_time,
+        end_time,
+        exclude_appointment_id,
+        staff_id,
+        joined_session_id
+    )
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| BookingError::internal(e.to_string()))?;
+
+    if is_taken {
+        return Err(BookingError::conflict(
+            "This time slot is no longer available.".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+// Every resource the service needs must have room left for the window
+async fn check_resources(
+    tx: &mut Transaction<'_, Postgres>,
+    service_id: Uuid,
+    (start_time, end_time): (OffsetDateTime, OffsetDateTime),
+    exclude_appointment_id: Option<Uuid>,
+    holds: &[SlotHold],
+) -> Result<(), BookingError> {
+    let reserved = resource_blocked_periods(
+        tx,
+        service_id,
+        (start_time, end_time),
+        exclude_appointment_id,
+        holds,
+    )
+    .await
+    .map_err(|e| BookingError::internal(e.to_string()))?;
+
+    if reserved.iter().any(|(reserved_start, reserved_end)| {
+        *reserved_start < end_time && *reserved_end > start_time
+    }) {
+        return Err(BookingError::conflict(
+            "A resource needed for this service is fully booked at the requested time.".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+// Re-checks Google free/busy for the exact window being booked
+async fn check_calendar_is_free(
+    http_client: &reqwest::Client,
+    access_token: &str,
+    calendar_id: &str,
+    start_time: OffsetDateTime,
+    end_time: OffsetDateTime,
+    ignored_periods: &[(OffsetDateTime, OffsetDateTime)],
+) -> Result<(), BookingError> {
+    let busy_periods =
+        fetch_busy_periods(http_client, access_token, calendar_id, start_time, end_time)
+            .await
+            .map_err(BookingError::internal)?;
+
+    check_busy_periods(&busy_periods, ignored_periods, (start_time, end_time))
+}
+
+// Google merges adjacent events, so cut the ignored windows out rather
+// than dropping only exact matches
+fn check_busy_periods(
+    busy_periods: &[(OffsetDateTime, OffsetDateTime)],
+    ignored_periods: &[(OffsetDateTime, OffsetDateTime)],
+    (start_time, end_time): (OffsetDateTime, OffsetDateTime),
+) -> Result<(), BookingError> {
+    let is_busy = busy_periods
+        .iter()
+        .flat_map(|period| subtract_periods(*period, ignored_periods))
+        .any(|(busy_start, busy_end)| busy_start < end_time && busy_end > start_time);
+
+    if is_busy {
+        return Err(business_unavailable_error());
+    }
+
+    Ok(())
+}
+
+fn business_unavailable_error() -> BookingError {
+    BookingError::conflict("The business is not available at the requested time.".to_string())
+}
+
+// A single lane explains itself, with several none of them could take it
+fn no_free_lane_error(lane_count: usize, last_error: Option<BookingError>) -> BookingError {
+    match last_error {
+        Some(error) if lane_count == 1 => error,
+        _ => BookingError::conflict("No staff member is free at the requested time.".to_string()),
+    }
+}
+
+// The exclusion constraint is the last line of defence against double booking
+fn booking_write_error(e: sqlx::Error) -> BookingError {
+    match &e {
+        sqlx::Error::Database(db_err) if db_err.code().as_deref() == Some("23P01") => {
+            BookingError::conflict("This time slot is no longer available.".to_string())
+        }
+
+        _ => BookingError::internal(e.to_string()),
+    }
+}
+
+// Fresh Google access token for the business, `None` when no calendar is connected
+async fn business_access_token(
+    tx: &mut Transaction<'_, Postgres>,
+    config: &web::Data<Config>,
+    http_client: &reqwest::Client,
+    business_id: Uuid,
+) -> Result<Option<String>, HttpResponse> {
+    let auth_record = sqlx::query_as!(
+        Auth,
+        r#"SELECT * FROM auth WHERE user_id = $1"#,
+        business_id
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| internal_server_error_response(e.to_string()))?;
+
+    match auth_record.and_then(|a| a.refresh_token) {
+        Some(refresh_token) => get_new_access_token(config.clone(), http_client, refresh_token)
+            .await
+            .map(Some)
+            .map_err(|e| {
+                internal_server_error_response(format!("Failed to refresh Google token: {}", e))
+            }),
+
+        None => Ok(None),
+    }
+}
+
+// The booking's calendar and event id. Bookings made before those were
+// stored are looked up on the primary calendar through their appointment id.
+async fn calendar_event_ref(
+    http_client: &reqwest::Client,
+    access_token: &str,
+    appointment: &Appointment,
+) -> Result<Option<(String, String)>, String> {
+    if let (Some(calendar_id), Some(event_id)) = (
+        appointment.google_calendar_id.as_deref(),
+        appointment.google_event_id.as_deref(),
+    ) {
+        return Ok(Some((calendar_id.to_string(), event_id.to_string())));
+    }
+
+    // Series always store their instances, an occurrence without one never synced
+    if appointment.series_id.is_some() {
+        return Ok(None);
+    }
+
+    let event_id =
+        find_appointment_event_id(http_client, access_token, PRIMARY_CALENDAR, appointment.id)
+            .await?;
+
+    Ok(event_id.map(|event_id| (PRIMARY_CALENDAR.to_string(), event_id)))
+}
+
+// The stored etag keeps a reschedule from undoing a move made in Google
+// Calendar. Other edits, like the customer answering the invite, change it
+// too, so on a mismatch the event only has to still sit at the booked time.
+async fn move_calendar_event(
+    http_client: &reqwest::Client,
+    access_token: &str,
+    (calendar_id, event_id): (&str, &str),
+    appointment: &Appointment,
+    times: &GoogleEventTimesPatch,
+) -> Result<GoogleEventItem, HttpResponse> {
+    let patched = patch_calendar_event_times(
+        http_client,
+        access_token,
+        calendar_id,
+        event_id,
+        appointment.google_event_etag.as_deref(),
+        times,
+    )
+    .await
+    .map_err(internal_server_error_response)?;
+
+    if let Some(event) = patched {
+        return Ok(event);
+    }
+
+    let current = get_calendar_event(http_client, access_token, calendar_id, event_id)
+        .await
+        .map_err(internal_server_error_response)?;
+
+    if !event_spans(
+        &current,
+        appointment.appointment_start_time,
+        appointment.appointment_end_time,
+    ) {
+        return Err(conflict_reponse(
+            "The calendar event was moved in Google Calendar. Update it there first.".to_string(),
+        ));
+    }
+
+    patch_calendar_event_times(
+        http_client,
+        access_token,
+        calendar_id,
+        event_id,
+        current.etag.as_deref(),
+        times,
+    )
+    .await
+    .map_err(internal_server_error_response)?
+    .ok_or_else(|| {
+        conflict_reponse("The calendar event changed during the reschedule.".to_string())
+    })
+}
+
+async fn remove_appointment_event(
+    http_client: &reqwest::Client,
+    access_token: &str,
+    appointment: &Appointment,
+) -> Result<(), String> {
+    match calendar_event_ref(http_client, access_token, appointment).await? {
+        Some((calendar_id, event_id)) => {
+            delete_calendar_event(http_client, access_token, &calendar_id, &event_id).await
+        }
+
+        None => Ok(()),
+    }
+}
+
+// Ends the recurring event of a series before `appointment`, or removes it when
+// nothing of the series came earlier. Hands back what it takes to restore it.
+async fn end_series_event(
+    tx: &mut Transaction<'_, Postgres>,
+    http_client: &reqwest::Client,
+    access_token: &str,
+    series_id: Uuid,
+    appointment: &Appointment,
+) -> Result<Option<(String, String, Vec<String>)>, String> {
+    let series = sqlx::query_as!(
+        AppointmentSeries,
+        "SELECT * FROM appointment_series WHERE id = $1",
+        series_id
+    )
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let (Some(calendar_id), Some(event_id)) = (series.google_calendar_id, series.google_event_id)
+    else {
+        return Ok(None);
+    };
+
+    let has_earlier = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM appointments
+            WHERE series_id = $1 AND appointment_start_time < $2
+        ) as "has_earlier!: bool"
+        "#,
+        series_id,
+        appointment.appointment_start_time
+    )
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if !has_earlier {
+        delete_calendar_event(http_client, access_token, &calendar_id, &event_id).await?;
+        return Ok(None);
+    }
+
+    let event = get_calendar_event(http_client, access_token, &calendar_id, &event_id).await?;
+    let recurrence = end_recurrence_before(&event.recurrence, appointment.appointment_start_time);
+
+    patch_calendar_event_recurrence(
+        http_client,
+        access_token,
+        &calendar_id,
+        &event_id,
+        &recurrence,
+    )
+    .await?;
+
+    Ok(Some((calendar_id, event_id, event.recurrence)))
 }
 
 /* -------------------------------------------------------------------------- */
@@ -436,7 +3165,14 @@ pub fn appointment_config(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/appointments")
             .route("", web::post().to(create_appointment))
+            .route("/series", web::post().to(create_appointment_series))
+            .route("/holds", web::post().to(create_slot_hold))
             .route("", web::get().to(get_all_appointments))
-            .route("/{id}", web::get().to(get_appointment_by_id)),
+            .route("/{id}", web::get().to(get_appointment_by_id))
+            .route("/{id}", web::patch().to(reschedule_appointment))
+            .route("/{id}/cancel", web::post().to(cancel_appointment))
+            .route("/{id}/confirm", web::post().to(confirm_appointment))
+            .route("/{id}/complete", web::post().to(complete_appointment))
+            .route("/{id}/no-show", web::post().to(mark_appointment_no_show)),
     );
 }
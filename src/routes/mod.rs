@@ -1,5 +1,8 @@
 pub mod appointment_routes;
 pub mod auth_routes;
+pub mod resource_routes;
 pub mod service_routes;
+pub mod staff_routes;
 pub mod user_routes;
 pub mod utils_routes;
+pub mod waitlist_routes;
@@ -0,0 +1,436 @@
+use crate::{
+    middlewares::auth_middleware::AuthenticatedUser,
+    routes::utils_routes::{
+        bad_request_response, internal_server_error_response, not_found_response,
+        validate_business_services,
+    },
+    structs::{
+        db_struct::{CreateResource, Resource, ResourceWithServices, UpdateResource},
+        response_struct::ApiResponse,
+        util_struct::ResourceQuery,
+    },
+    utils::cache_utils::invalidate_all_business_slot_cache,
+};
+use actix_web::{HttpResponse, Responder, web};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+#[utoipa::path(
+    post,
+    path = "/resources",
+    tag = "Resources",
+    request_body = CreateResource,
+    responses(
+        (status = 201, body = ApiResponse<ResourceWithServices>),
+        (status = 400, description = "Bad Request"),
+        (status = 500, description = "Internal Server Error")
+    )
+)]
+pub async fn create_resource(
+    user: AuthenticatedUser,
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<deadpool_redis::Pool>,
+    body: web::Json<CreateResource>,
+) -> impl Responder {
+    let user_id = user.user_id;
+    let new_resource = body.into_inner();
+    let name = new_resource.name.trim().to_string();
+    let capacity = new_resource.capacity.unwrap_or(1);
+
+    if name.is_empty() {
+        return bad_request_response("Resource name cannot be empty.".to_string());
+    }
+
+    if capacity < 1 {
+        return bad_request_response("Resource capacity must be at least 1.".to_string());
+    }
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return internal_server_error_response(e.to_string()),
+    };
+
+    let resource = match sqlx::query_as!(
+        Resource,
+        r#"
+        INSERT INTO resources (business_id, name, capacity)
+        VALUES ($1, $2, $3)
+        RETURNING *
+        "#,
+        user_id,
+        name,
+        capacity
+    )
+    .fetch_one(&mut *tx)
+    .await
+    {
+        Ok(resource) => resource,
+        Err(e) => {
+            tx.rollback().await.ok();
+            return internal_server_error_response(e.to_string());
+        }
+    };
+
+    let service_ids =
+        match replace_resource_services(&mut tx, user_id, resource.id, &new_resource.service_ids)
+            .await
+        {
+            Ok(ids) => ids,
+            Err(response) => {
+                tx.rollback().await.ok();
+                return response;
+            }
+        };
+
+    if let Err(e) = tx.commit().await {
+        return internal_server_error_response(e.to_string());
+    }
+
+    invalidate_all_business_slot_cache(&redis_pool, user_id).await;
+
+    HttpResponse::Created().json(ApiResponse {
+        success: true,
+        data: Some(ResourceWithServices {
+            resource,
+            service_ids,
+        }),
+        message: Some("Resource created successfully.".to_string()),
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+#[utoipa::path(
+    get,
+    path = "/resources",
+    tag = "Resources",
+    params(ResourceQuery),
+    responses(
+        (status = 200, body = ApiResponse<Vec<ResourceWithServices>>),
+        (status = 500, description = "Internal Server Error")
+    )
+)]
+pub async fn get_all_resources(
+    query: web::Query<ResourceQuery>,
+    pool: web::Data<PgPool>,
+) -> impl Responder {
+    let resources = match sqlx::query_as!(
+        Resource,
+        r#"
+        SELECT * FROM resources
+        WHERE business_id = $1
+        ORDER BY name, id
+        "#,
+        query.business_id
+    )
+    .fetch_all(pool.get_ref())
+    .await
+    {
+        Ok(resources) => resources,
+        Err(e) => return internal_server_error_response(e.to_string()),
+    };
+
+    let links = match sqlx::query!(
+        r#"
+        SELECT sr.resource_id, sr.service_id FROM service_resources sr
+        JOIN resources r ON r.id = sr.resource_id
+        WHERE r.business_id = $1
+        "#,
+        query.business_id
+    )
+    .fetch_all(pool.get_ref())
+    .await
+    {
+        Ok(links) => links,
+        Err(e) => return internal_server_error_response(e.to_string()),
+    };
+
+    let resources_with_services: Vec<ResourceWithServices> = resources
+        .into_iter()
+        .map(|resource| ResourceWithServices {
+            service_ids: links
+                .iter()
+                .filter(|link| link.resource_id == resource.id)
+                .map(|link| link.service_id)
+                .collect(),
+            resource,
+        })
+        .collect();
+
+    HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        data: Some(resources_with_services),
+        message: None,
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+#[utoipa::path(
+    patch,
+    path = "/resources/{id}",
+    tag = "Resources",
+    params(("id" = Uuid, Path, description = "Resource ID")),
+    request_body = UpdateResource,
+    responses(
+        (status = 200, body = ApiResponse<ResourceWithServices>),
+        (status = 400, description = "Bad Request"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error")
+    )
+)]
+pub async fn update_resource(
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<deadpool_redis::Pool>,
+    body: web::Json<UpdateResource>,
+) -> impl Responder {
+    let resource_id = path.into_inner();
+    let user_id = user.user_id;
+    let mut fields_to_update = body.into_inner();
+
+    if let Some(name) = fields_to_update.name.take() {
+        if name.trim().is_empty() {
+            return bad_request_response("Resource name cannot be empty.".to_string());
+        }
+
+        fields_to_update.name = Some(name.trim().to_string());
+    }
+
+    if let Some(capacity) = fields_to_update.capacity
+        && capacity < 1
+    {
+        return bad_request_response("Resource capacity must be at least 1.".to_string());
+    }
+
+    if let Err(response) = load_owned_resource(&pool, user_id, resource_id).await {
+        return response;
+    }
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return internal_server_error_response(e.to_string()),
+    };
+
+    // Lowering the capacity leaves existing bookings alone, it only limits new ones
+    let resource = match sqlx::query_as!(
+        Resource,
+        r#"
+        UPDATE resources SET
+            name = COALESCE($1, name),
+            capacity = COALESCE($2, capacity),
+            updated_at = NOW()
+        WHERE id = $3
+        RETURNING *
+        "#,
+        fields_to_update.name,
+        fields_to_update.capacity,
+        resource_id
+    )
+    .fetch_one(&mut *tx)
+    .await
+    {
+        Ok(resource) => resource,
+        Err(e) => {
+            tx.rollback().await.ok();
+            return internal_server_error_response(e.to_string());
+        }
+    };
+
+    let service_ids = match fields_to_update.service_ids {
+        Some(service_ids) => {
+            match replace_resource_services(&mut tx, user_id, resource_id, &service_ids).await {
+                Ok(ids) => ids,
+                Err(response) => {
+                    tx.rollback().await.ok();
+                    return response;
+                }
+            }
+        }
+
+        None => match sqlx::query_scalar!(
+            "SELECT service_id FROM service_resources WHERE resource_id = $1",
+            resource_id
+        )
+        .fetch_all(&mut *tx)
+        .await
+        {
+            Ok(ids) => ids,
+            Err(e) => {
+                tx.rollback().await.ok();
+                return internal_server_error_response(e.to_string());
+            }
+        },
+    };
+
+    if let Err(e) = tx.commit().await {
+        return internal_server_error_response(e.to_string());
+    }
+
+    invalidate_all_business_slot_cache(&redis_pool, user_id).await;
+
+    HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        data: Some(ResourceWithServices {
+            resource,
+            service_ids,
+        }),
+        message: Some("Resource updated successfully.".to_string()),
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+#[utoipa::path(
+    delete,
+    path = "/resources/{id}",
+    tag = "Resources",
+    params(("id" = Uuid, Path, description = "Resource ID")),
+    responses(
+        (status = 200, description = "Resource deleted"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error")
+    )
+)]
+pub async fn delete_resource(
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<deadpool_redis::Pool>,
+) -> impl Responder {
+    let resource_id = path.into_inner();
+    let user_id = user.user_id;
+
+    if let Err(response) = load_owned_resource(&pool, user_id, resource_id).await {
+        return response;
+    }
+
+    // Service links and existing reservations of the resource go with it
+    if let Err(e) = sqlx::query!("DELETE FROM resources WHERE id = $1", resource_id)
+        .execute(pool.get_ref())
+        .await
+    {
+        return internal_server_error_response(e.to_string());
+    }
+
+    invalidate_all_business_slot_cache(&redis_pool, user_id).await;
+
+    HttpResponse::Ok().json(ApiResponse::<()> {
+        success: true,
+        data: None,
+        message: Some("Resource deleted successfully.".to_string()),
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+async fn load_owned_resource(
+    pool: &PgPool,
+    user_id: Uuid,
+    resource_id: Uuid,
+) -> Result<Resource, HttpResponse> {
+    let resource = match sqlx::query_as!(
+        Resource,
+        "SELECT * FROM resources WHERE id = $1",
+        resource_id
+    )
+    .fetch_one(pool)
+    .await
+    {
+        Ok(resource) => resource,
+        Err(sqlx::Error::RowNotFound) => {
+            return Err(not_found_response("Resource not found.".to_string()));
+        }
+
+        Err(e) => return Err(internal_server_error_response(e.to_string())),
+    };
+
+    // Check ownership
+    if resource.business_id != user_id {
+        return Err(HttpResponse::Forbidden().json(ApiResponse::<()> {
+            success: false,
+            data: None,
+            message: Some("You do not have permission to edit this resource.".to_string()),
+        }));
+    }
+
+    Ok(resource)
+}
+
+async fn replace_resource_services(
+    tx: &mut Transaction<'_, Postgres>,
+    business_id: Uuid,
+    resource_id: Uuid,
+    service_ids: &[Uuid],
+) -> Result<Vec<Uuid>, HttpResponse> {
+    let service_ids = validate_business_services(tx, business_id, service_ids).await?;
+
+    sqlx::query!(
+        "DELETE FROM service_resources WHERE resource_id = $1",
+        resource_id
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| internal_server_error_response(e.to_string()))?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO service_resources (service_id, resource_id)
+        SELECT UNNEST($1::UUID[]), $2
+        "#,
+        &service_ids,
+        resource_id
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| internal_server_error_response(e.to_string()))?;
+
+    Ok(service_ids)
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+pub fn resource_config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/resources")
+            .route("", web::post().to(create_resource))
+            .route("", web::get().to(get_all_resources))
+            .route("/{id}", web::patch().to(update_resource))
+            .route("/{id}", web::delete().to(delete_resource)),
+    );
+}
@@ -3,13 +3,21 @@ use std::time::Duration;
 use crate::{
     config::Config,
     middlewares::auth_middleware::AuthenticatedUser,
-    routes::utils_routes::{internal_server_error_response, not_found_response},
+    routes::utils_routes::{
+        bad_request_response, internal_server_error_response, not_found_response,
+        validate_booking_window, validate_service_capacity,
+    },
     structs::{
-        db_struct::{CreateService, Service, UpdateService},
+        db_struct::{
+            CreateService, Service, ServiceAvailabilityRule, SetServiceAvailability, UpdateService,
+        },
         response_struct::ApiResponse,
-        util_struct::UploadResponse,
+        util_struct::{AvailabilityRuleError, UploadResponse},
+    },
+    utils::{
+        auth_utils::get_gcs_client, cache_utils::invalidate_all_service_slot_cache,
+        others_utils::validate_weekly_rules,
     },
-    utils::auth_utils::get_gcs_client,
 };
 use actix_web::{HttpResponse, Responder, web};
 use gcloud_storage::sign::{SignedURLMethod, SignedURLOptions};
@@ -50,14 +58,39 @@ pub async fn create_service(
         new_service.service_name = new_service.service_name.trim().to_string();
     }
 
+    if let Err(response) = validate_buffers(
+        new_service.buffer_before_minutes,
+        new_service.buffer_after_minutes,
+    ) {
+        return response;
+    }
+
+    if let Err(response) = validate_slot_interval(new_service.slot_interval_minutes) {
+        return response;
+    }
+
+    if let Err(response) =
+        validate_booking_window(new_service.min_notice_minutes, new_service.max_advance_days)
+    {
+        return response;
+    }
+
+    if let Err(response) = validate_service_capacity(new_service.capacity) {
+        return response;
+    }
+
     match sqlx::query_as!(
         Service,
         r#"
         INSERT INTO services (
             user_id, service_name, description, price, 
-            duration_minutes, category
+            duration_minutes, category, buffer_before_minutes, buffer_after_minutes,
+            slot_interval_minutes, min_notice_minutes, max_advance_days, capacity
+        )
+        VALUES (
+            $1, $2, $3, $4, $5, $6, COALESCE($7, 0), COALESCE($8, 0), $9, $10, $11,
+            COALESCE($12, 1)
         )
-        VALUES ($1, $2, $3, $4, $5, $6)
         RETURNING *
         "#,
         user_id,
@@ -65,7 +98,13 @@ pub async fn create_service(
         new_service.description,
         new_service.price,
         new_service.duration_minutes,
-        new_service.category
+        new_service.category,
+        new_service.buffer_before_minutes,
+        new_service.buffer_after_minutes,
+        new_service.slot_interval_minutes,
+        new_service.min_notice_minutes,
+        new_service.max_advance_days,
+        new_service.capacity
     )
     .fetch_one(pool.get_ref())
     .await
@@ -228,6 +267,7 @@ pub async fn update_service(
     user: AuthenticatedUser,
     pool: web::Data<PgPool>,
     body: web::Json<UpdateService>,
+    redis_pool: web::Data<deadpool_redis::Pool>,
 ) -> impl Responder {
     let service_id = path.into_inner();
     let user_id = user.user_id;
@@ -242,6 +282,28 @@ pub async fn update_service(
         }
     }
 
+    if let Err(response) = validate_buffers(
+        fields_to_update.buffer_before_minutes,
+        fields_to_update.buffer_after_minutes,
+    ) {
+        return response;
+    }
+
+    if let Err(response) = validate_slot_interval(fields_to_update.slot_interval_minutes) {
+        return response;
+    }
+
+    if let Err(response) = validate_booking_window(
+        fields_to_update.min_notice_minutes,
+        fields_to_update.max_advance_days,
+    ) {
+        return response;
+    }
+
+    if let Err(response) = validate_service_capacity(fields_to_update.capacity) {
+        return response;
+    }
+
     let service_to_update =
         match sqlx::query_as!(Service, "SELECT * FROM services WHERE id = $1", service_id)
             .fetch_one(pool.get_ref())
@@ -277,8 +339,14 @@ pub async fn update_service(
             price = COALESCE($3, price),
             duration_minutes = COALESCE($4, duration_minutes),
             category = COALESCE($5, category),
+            buffer_before_minutes = COALESCE($6, buffer_before_minutes),
+            buffer_after_minutes = COALESCE($7, buffer_after_minutes),
+            slot_interval_minutes = COALESCE($8, slot_interval_minutes),
+            min_notice_minutes = COALESCE($9, min_notice_minutes),
+            max_advance_days = COALESCE($10, max_advance_days),
+            capacity = COALESCE($11, capacity),
             updated_at = NOW()
-        WHERE id = $6
+        WHERE id = $12
         RETURNING *
         "#,
         fields_to_update.service_name,
@@ -286,16 +354,27 @@ pub async fn update_service(
         fields_to_update.price,
         fields_to_update.duration_minutes,
         fields_to_update.category,
+        fields_to_update.buffer_before_minutes,
+        fields_to_update.buffer_after_minutes,
+        fields_to_update.slot_interval_minutes,
+        fields_to_update.min_notice_minutes,
+        fields_to_update.max_advance_days,
+        fields_to_update.capacity,
         service_id
     )
     .fetch_one(pool.get_ref())
     .await
     {
-        Ok(service) => HttpResponse::Ok().json(ApiResponse {
-            success: true,
-            data: Some(service),
-            message: Some("Service updated successfully".to_string()),
-        }),
+        Ok(service) => {
+            // Duration, interval and capacity all shape the cached slots
+            invalidate_all_service_slot_cache(&redis_pool, user_id, service_id).await;
+
+            HttpResponse::Ok().json(ApiResponse {
+                success: true,
+                data: Some(service),
+                message: Some("Service updated successfully".to_string()),
+            })
+        }
 
         Err(e) => internal_server_error_response(e.to_string()),
     }
@@ -490,12 +569,215 @@ pub async fn get_service_upload_url(
 /*                                      -                                     */
 /* -------------------------------------------------------------------------- */
 
+#[utoipa::path(
+    get,
+    path = "/services/{id}/availability",
+    tag = "Services",
+    params(("id" = Uuid, Path, description = "Service ID")),
+    responses(
+        (status = 200, body = ApiResponse<Vec<ServiceAvailabilityRule>>),
+        (status = 500, description = "Internal Server Error")
+    )
+)]
+pub async fn get_service_availability(
+    path: web::Path<Uuid>,
+    pool: web::Data<PgPool>,
+) -> impl Responder {
+    let service_id = path.into_inner();
+
+    match sqlx::query_as!(
+        ServiceAvailabilityRule,
+        r#"
+        SELECT * FROM service_availability
+        WHERE service_id = $1
+        ORDER BY day_of_week, open_time
+        "#,
+        service_id
+    )
+    .fetch_all(pool.get_ref())
+    .await
+    {
+        Ok(rules) => HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(rules),
+            message: None,
+        }),
+
+        Err(e) => internal_server_error_response(e.to_string()),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+#[utoipa::path(
+    post,
+    path = "/services/{id}/availability",
+    tag = "Services",
+    params(("id" = Uuid, Path, description = "Service ID")),
+    request_body = SetServiceAvailability,
+    responses(
+        (status = 200, body = ApiResponse<Vec<ServiceAvailabilityRule>>),
+        (status = 400, body = ApiResponse<Vec<AvailabilityRuleError>>, description = "Invalid Rules"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error")
+    )
+)]
+pub async fn set_service_availability(
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<deadpool_redis::Pool>,
+    body: web::Json<SetServiceAvailability>,
+) -> impl Responder {
+    let service_id = path.into_inner();
+    let user_id = user.user_id;
+
+    // Same checks as the business schedule it is narrowing
+    let rules = match validate_weekly_rules(&body.rules) {
+        Ok(r) => r,
+        Err(errors) => {
+            return HttpResponse::BadRequest().json(ApiResponse {
+                success: false,
+                data: Some(errors),
+                message: Some("Invalid availability rules.".to_string()),
+            });
+        }
+    };
+
+    let service = match sqlx::query_as!(Service, "SELECT * FROM services WHERE id = $1", service_id)
+        .fetch_one(pool.get_ref())
+        .await
+    {
+        Ok(service) => service,
+
+        Err(sqlx::Error::RowNotFound) => {
+            return not_found_response("Service not found".to_string());
+        }
+
+        Err(e) => return internal_server_error_response(e.to_string()),
+    };
+
+    // Check ownership
+    if service.user_id != user_id {
+        return HttpResponse::Forbidden().json(ApiResponse::<()> {
+            success: false,
+            data: None,
+            message: Some("You do not have permission to edit this service.".to_string()),
+        });
+    }
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return internal_server_error_response(e.to_string()),
+    };
+
+    if let Err(e) = sqlx::query!(
+        "DELETE FROM service_availability WHERE service_id = $1",
+        service_id
+    )
+    .execute(&mut *tx)
+    .await
+    {
+        tx.rollback().await.ok();
+        return internal_server_error_response(e.to_string());
+    }
+
+    let mut saved_rules = Vec::new();
+
+    for (day_of_week, open_time, close_time) in rules {
+        match sqlx::query_as!(
+            ServiceAvailabilityRule,
+            r#"
+            INSERT INTO service_availability (service_id, day_of_week, open_time, close_time)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+            "#,
+            service_id,
+            day_of_week,
+            open_time,
+            close_time
+        )
+        .fetch_one(&mut *tx)
+        .await
+        {
+            Ok(rule) => saved_rules.push(rule),
+            Err(e) => {
+                tx.rollback().await.ok();
+                return internal_server_error_response(e.to_string());
+            }
+        }
+    }
+
+    if let Err(e) = tx.commit().await {
+        return internal_server_error_response(e.to_string());
+    }
+
+    invalidate_all_service_slot_cache(&redis_pool, user_id, service_id).await;
+
+    HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        data: Some(saved_rules),
+        message: Some("Service availability updated successfully.".to_string()),
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+fn validate_buffers(before: Option<i32>, after: Option<i32>) -> Result<(), HttpResponse> {
+    if before.is_some_and(|m| m < 0) || after.is_some_and(|m| m < 0) {
+        return Err(bad_request_response(
+            "Buffer minutes cannot be negative.".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+// Shared with businesses, which set the default slot interval
+pub fn validate_slot_interval(interval_minutes: Option<i32>) -> Result<(), HttpResponse> {
+    if interval_minutes.is_some_and(|m| !(5..=240).contains(&m)) {
+        return Err(bad_request_response(
+            "Slot interval must be between 5 and 240 minutes.".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
 pub fn service_config(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/services")
             .route("", web::post().to(create_service))
             .route("", web::get().to(get_all_services))
             .route("/{id}/upload-url", web::get().to(get_service_upload_url))
+            .route(
+                "/{id}/availability",
+                web::get().to(get_service_availability),
+            )
+            .route(
+                "/{id}/availability",
+                web::post().to(set_service_availability),
+            )
             .route("/{id}", web::get().to(get_service_by_id))
             .route("/{id}", web::patch().to(update_service))
             .route("/{id}", web::delete().to(delete_service)),
@@ -0,0 +1,535 @@
+use crate::{
+    middlewares::auth_middleware::AuthenticatedUser,
+    routes::utils_routes::{
+        bad_request_response, internal_server_error_response, not_found_response,
+        validate_business_services,
+    },
+    structs::{
+        db_struct::{
+            CreateStaff, SetStaffAvailability, Staff, StaffAvailabilityRule, StaffWithServices,
+            UpdateStaff,
+        },
+        response_struct::ApiResponse,
+        util_struct::{AvailabilityRuleError, StaffQuery},
+    },
+    utils::{cache_utils::invalidate_all_business_slot_cache, others_utils::validate_weekly_rules},
+};
+use actix_web::{HttpResponse, Responder, web};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+#[utoipa::path(
+    post,
+    path = "/staff",
+    tag = "Staff",
+    request_body = CreateStaff,
+    responses(
+        (status = 201, body = ApiResponse<StaffWithServices>),
+        (status = 400, description = "Bad Request"),
+        (status = 500, description = "Internal Server Error")
+    )
+)]
+pub async fn create_staff(
+    user: AuthenticatedUser,
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<deadpool_redis::Pool>,
+    body: web::Json<CreateStaff>,
+) -> impl Responder {
+    let user_id = user.user_id;
+    let new_staff = body.into_inner();
+    let name = new_staff.name.trim().to_string();
+
+    if name.is_empty() {
+        return bad_request_response("Staff name cannot be empty.".to_string());
+    }
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return internal_server_error_response(e.to_string()),
+    };
+
+    let staff = match sqlx::query_as!(
+        Staff,
+        r#"
+        INSERT INTO staff (business_id, name, email, google_calendar_id)
+        VALUES ($1, $2, $3, $4)
+        RETURNING *
+        "#,
+        user_id,
+        name,
+        new_staff.email,
+        new_staff.google_calendar_id
+    )
+    .fetch_one(&mut *tx)
+    .await
+    {
+        Ok(staff) => staff,
+        Err(e) => {
+            tx.rollback().await.ok();
+            return internal_server_error_response(e.to_string());
+        }
+    };
+
+    let service_ids =
+        match replace_staff_services(&mut tx, user_id, staff.id, &new_staff.service_ids).await {
+            Ok(ids) => ids,
+            Err(response) => {
+                tx.rollback().await.ok();
+                return response;
+            }
+        };
+
+    if let Err(e) = tx.commit().await {
+        return internal_server_error_response(e.to_string());
+    }
+
+    // The first staff member changes who takes the bookings
+    invalidate_all_business_slot_cache(&redis_pool, user_id).await;
+
+    HttpResponse::Created().json(ApiResponse {
+        success: true,
+        data: Some(StaffWithServices { staff, service_ids }),
+        message: Some("Staff member created successfully.".to_string()),
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+#[utoipa::path(
+    get,
+    path = "/staff",
+    tag = "Staff",
+    params(StaffQuery),
+    responses(
+        (status = 200, body = ApiResponse<Vec<StaffWithServices>>),
+        (status = 500, description = "Internal Server Error")
+    )
+)]
+pub async fn get_all_staff(
+    query: web::Query<StaffQuery>,
+    pool: web::Data<PgPool>,
+) -> impl Responder {
+    let staff = match sqlx::query_as!(
+        Staff,
+        r#"
+        SELECT * FROM staff
+        WHERE business_id = $1 AND is_active
+        ORDER BY name, id
+        "#,
+        query.business_id
+    )
+    .fetch_all(pool.get_ref())
+    .await
+    {
+        Ok(staff) => staff,
+        Err(e) => return internal_server_error_response(e.to_string()),
+    };
+
+    let links = match sqlx::query!(
+        r#"
+        SELECT ss.staff_id, ss.service_id FROM staff_services ss
+        JOIN staff st ON st.id = ss.staff_id
+        WHERE st.business_id = $1
+        "#,
+        query.business_id
+    )
+    .fetch_all(pool.get_ref())
+    .await
+    {
+        Ok(links) => links,
+        Err(e) => return internal_server_error_response(e.to_string()),
+    };
+
+    let staff_with_services: Vec<StaffWithServices> = staff
+        .into_iter()
+        .map(|staff| StaffWithServices {
+            service_ids: links
+                .iter()
+                .filter(|link| link.staff_id == staff.id)
+                .map(|link| link.service_id)
+                .collect(),
+            staff,
+        })
+        .collect();
+
+    HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        data: Some(staff_with_services),
+        message: None,
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+#[utoipa::path(
+    patch,
+    path = "/staff/{id}",
+    tag = "Staff",
+    params(("id" = Uuid, Path, description = "Staff ID")),
+    request_body = UpdateStaff,
+    responses(
+        (status = 200, body = ApiResponse<StaffWithServices>),
+        (status = 400, description = "Bad Request"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error")
+    )
+)]
+pub async fn update_staff(
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<deadpool_redis::Pool>,
+    body: web::Json<UpdateStaff>,
+) -> impl Responder {
+    let staff_id = path.into_inner();
+    let user_id = user.user_id;
+    let mut fields_to_update = body.into_inner();
+
+    if let Some(name) = fields_to_update.name.take() {
+        if name.trim().is_empty() {
+            return bad_request_response("Staff name cannot be empty.".to_string());
+        }
+
+        fields_to_update.name = Some(name.trim().to_string());
+    }
+
+    if let Some(calendar_id) = fields_to_update.google_calendar_id.take() {
+        fields_to_update.google_calendar_id = Some(calendar_id.trim().to_string());
+    }
+
+    if let Err(response) = load_owned_staff(&pool, user_id, staff_id).await {
+        return response;
+    }
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return internal_server_error_response(e.to_string()),
+    };
+
+    // Deactivated staff keep their bookings but take no new ones
+    let staff = match sqlx::query_as!(
+        Staff,
+        r#"
+        UPDATE staff SET
+            name = COALESCE($1, name),
+            email = COALESCE($2, email),
+            google_calendar_id = CASE
+                WHEN $3::VARCHAR IS NULL THEN google_calendar_id
+                ELSE NULLIF($3, '')
+            END,
+            is_active = COALESCE($4, is_active),
+            updated_at = NOW()
+        WHERE id = $5
+        RETURNING *
+        "#,
+        fields_to_update.name,
+        fields_to_update.email,
+        fields_to_update.google_calendar_id,
+        fields_to_update.is_active,
+        staff_id
+    )
+    .fetch_one(&mut *tx)
+    .await
+    {
+        Ok(staff) => staff,
+        Err(e) => {
+            tx.rollback().await.ok();
+            return internal_server_error_response(e.to_string());
+        }
+    };
+
+    let service_ids = match fields_to_update.service_ids {
+        Some(service_ids) => {
+            match replace_staff_services(&mut tx, user_id, staff_id, &service_ids).await {
+                Ok(ids) => ids,
+                Err(response) => {
+                    tx.rollback().await.ok();
+                    return response;
+                }
+            }
+        }
+
+        None => match sqlx::query_scalar!(
+            "SELECT service_id FROM staff_services WHERE staff_id = $1",
+            staff_id
+        )
+        .fetch_all(&mut *tx)
+        .await
+        {
+            Ok(ids) => ids,
+            Err(e) => {
+                tx.rollback().await.ok();
+                return internal_server_error_response(e.to_string());
+            }
+        },
+    };
+
+    if let Err(e) = tx.commit().await {
+        return internal_server_error_response(e.to_string());
+    }
+
+    invalidate_all_business_slot_cache(&redis_pool, user_id).await;
+
+    HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        data: Some(StaffWithServices { staff, service_ids }),
+        message: Some("Staff member updated successfully.".to_string()),
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+#[utoipa::path(
+    get,
+    path = "/staff/{id}/availability",
+    tag = "Staff",
+    params(("id" = Uuid, Path, description = "Staff ID")),
+    responses(
+        (status = 200, body = ApiResponse<Vec<StaffAvailabilityRule>>),
+        (status = 500, description = "Internal Server Error")
+    )
+)]
+pub async fn get_staff_availability(
+    path: web::Path<Uuid>,
+    pool: web::Data<PgPool>,
+) -> impl Responder {
+    let staff_id = path.into_inner();
+
+    match sqlx::query_as!(
+        StaffAvailabilityRule,
+        r#"
+        SELECT * FROM staff_availability
+        WHERE staff_id = $1
+        ORDER BY day_of_week, open_time
+        "#,
+        staff_id
+    )
+    .fetch_all(pool.get_ref())
+    .await
+    {
+        Ok(rules) => HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(rules),
+            message: None,
+        }),
+
+        Err(e) => internal_server_error_response(e.to_string()),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+#[utoipa::path(
+    post,
+    path = "/staff/{id}/availability",
+    tag = "Staff",
+    params(("id" = Uuid, Path, description = "Staff ID")),
+    request_body = SetStaffAvailability,
+    responses(
+        (status = 200, body = ApiResponse<Vec<StaffAvailabilityRule>>),
+        (status = 400, body = ApiResponse<Vec<AvailabilityRuleError>>, description = "Invalid Rules"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error")
+    )
+)]
+pub async fn set_staff_availability(
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<deadpool_redis::Pool>,
+    body: web::Json<SetStaffAvailability>,
+) -> impl Responder {
+    let staff_id = path.into_inner();
+    let user_id = user.user_id;
+
+    // Same checks as the business schedule it is narrowing
+    let rules = match validate_weekly_rules(&body.rules) {
+        Ok(r) => r,
+        Err(errors) => {
+            return HttpResponse::BadRequest().json(ApiResponse {
+                success: false,
+                data: Some(errors),
+                message: Some("Invalid availability rules.".to_string()),
+            });
+        }
+    };
+
+    if let Err(response) = load_owned_staff(&pool, user_id, staff_id).await {
+        return response;
+    }
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return internal_server_error_response(e.to_string()),
+    };
+
+    if let Err(e) = sqlx::query!(
+        "DELETE FROM staff_availability WHERE staff_id = $1",
+        staff_id
+    )
+    .execute(&mut *tx)
+    .await
+    {
+        tx.rollback().await.ok();
+        return internal_server_error_response(e.to_string());
+    }
+
+    let mut saved_rules = Vec::new();
+
+    for (day_of_week, open_time, close_time) in rules {
+        match sqlx::query_as!(
+            StaffAvailabilityRule,
+            r#"
+            INSERT INTO staff_availability (staff_id, day_of_week, open_time, close_time)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+            "#,
+            staff_id,
+            day_of_week,
+            open_time,
+            close_time
+        )
+        .fetch_one(&mut *tx)
+        .await
+        {
+            Ok(rule) => saved_rules.push(rule),
+            Err(e) => {
+                tx.rollback().await.ok();
+                return internal_server_error_response(e.to_string());
+            }
+        }
+    }
+
+    if let Err(e) = tx.commit().await {
+        return internal_server_error_response(e.to_string());
+    }
+
+    invalidate_all_business_slot_cache(&redis_pool, user_id).await;
+
+    HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        data: Some(saved_rules),
+        message: Some("Staff availability updated successfully.".to_string()),
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+async fn load_owned_staff(
+    pool: &PgPool,
+    user_id: Uuid,
+    staff_id: Uuid,
+) -> Result<Staff, HttpResponse> {
+    let staff = match sqlx::query_as!(Staff, "SELECT * FROM staff WHERE id = $1", staff_id)
+        .fetch_one(pool)
+        .await
+    {
+        Ok(staff) => staff,
+
+        Err(sqlx::Error::RowNotFound) => {
+            return Err(not_found_response("Staff member not found".to_string()));
+        }
+
+        Err(e) => return Err(internal_server_error_response(e.to_string())),
+    };
+
+    // Check ownership
+    if staff.business_id != user_id {
+        return Err(HttpResponse::Forbidden().json(ApiResponse::<()> {
+            success: false,
+            data: None,
+            message: Some("You do not have permission to edit this staff member.".to_string()),
+        }));
+    }
+
+    Ok(staff)
+}
+
+async fn replace_staff_services(
+    tx: &mut Transaction<'_, Postgres>,
+    business_id: Uuid,
+    staff_id: Uuid,
+    service_ids: &[Uuid],
+) -> Result<Vec<Uuid>, HttpResponse> {
+    let service_ids = validate_business_services(tx, business_id, service_ids).await?;
+
+    sqlx::query!("DELETE FROM staff_services WHERE staff_id = $1", staff_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| internal_server_error_response(e.to_string()))?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO staff_services (staff_id, service_id)
+        SELECT $1, UNNEST($2::UUID[])
+        "#,
+        staff_id,
+        &service_ids
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| internal_server_error_response(e.to_string()))?;
+
+    Ok(service_ids)
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+pub fn staff_config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/staff")
+            .route("", web::post().to(create_staff))
+            .route("", web::get().to(get_all_staff))
+            .route("/{id}/availability", web::get().to(get_staff_availability))
+            .route("/{id}/availability", web::post().to(set_staff_availability))
+            .route("/{id}", web::patch().to(update_staff)),
+    );
+}
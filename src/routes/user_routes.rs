@@ -3,38 +3,64 @@
 use crate::{
     config::Config,
     middlewares::auth_middleware::AuthenticatedUser,
-    routes::utils_routes::{
-        bad_request_response, conflict_reponse, internal_server_error_response, not_found_response,
+    routes::{
+        service_routes::validate_slot_interval,
+        utils_routes::{
+            bad_request_response, conflict_reponse, internal_server_error_response,
+            not_found_response, validate_booking_window, validate_time_zone,
+        },
     },
     structs::{
         db_struct::{
-            Appointment, Auth, AvailabilityRule, Service, SetAvailability, UpdateUser, User,
-            UserStatus, UserWithServices,
+            Appointment, Auth, AvailabilityOverride, AvailabilityRule, BusinessBreak,
+            CreateAvailabilityOverride, Service, SetAvailability, SetAvailabilityOverride,
+            SetBreaks, Staff, UpdateUser, User, UserStatus, UserWithServices,
         },
         response_struct::{ApiResponse, EmptyStruct, MergedUserProfile},
         util_struct::{
-            FreeBusyRequest, FreeBusyRequestItem, FreeBusyResponse, SlotQuery, TimeSlot,
-            UploadQuery, UploadResponse,
+            AppointmentQuery, AvailabilityRuleError, BookingLane, DaySlots, GroupSession,
+            NextSlotQuery, OpeningSchedule, SharedBlockedPeriods, SlotHold, SlotQuery,
+            SlotRangeQuery, SlotSettings, TimeSlot, UploadQuery, UploadResponse,
         },
     },
     utils::{
         auth_utils::{get_gcs_client, get_new_access_token},
-        others_utils::{generate_slots, local_to_utc},
+        availability_utils::{
+            booking_lanes, breaks_between, business_time_zone, load_opening_schedule,
+            resource_blocked_periods, shared_calendar_bookings,
+        },
+        cache_utils::{
+            cache_slots, invalidate_all_business_slot_cache, invalidate_business_slot_cache,
+            slot_cache_key,
+        },
+        calendar_utils::{busy_calendar, fetch_busy_periods},
+        hold_utils::business_holds,
+        others_utils::{
+            apply_group_sessions, booking_window, convert_to_local_primitive,
+            cross_lane_hold_periods, generate_day_slots, hold_sessions, local_to_utc,
+            merge_lane_slots, override_windows_overlap, retain_bookable_slots, schedule_hours,
+            subtract_periods, validate_weekly_rules,
+        },
     },
 };
 use actix_web::{HttpResponse, Responder, web};
 use chrono_tz::Tz;
 use deadpool_redis::redis;
 use gcloud_storage::sign::{SignedURLMethod, SignedURLOptions};
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgPool, Postgres, Transaction};
 use std::str::FromStr;
 use std::{collections::HashMap, time::Duration};
-use time::{
-    Date, OffsetDateTime, PrimitiveDateTime, Time,
-    format_description::{self, well_known::Rfc3339},
-};
+use time::{Date, OffsetDateTime, PrimitiveDateTime, Time, format_description};
 use uuid::Uuid;
 
+// Longest span a single range query may cover
+const MAX_SLOT_RANGE_DAYS: i64 = 31;
+
+// Bounds for the next available slot search
+const MAX_NEXT_SLOTS: usize = 20;
+const MAX_NEXT_SLOT_LOOKAHEAD_DAYS: i64 = 90;
+const NEXT_SLOT_CHUNK_DAYS: i64 = 7;
+
 /* -------------------------------------------------------------------------- */
 /*                                      -                                     */
 /* -------------------------------------------------------------------------- */
@@ -223,10 +249,28 @@ pub async fn update_user(
     path: web::Path<Uuid>,
     updated_user: web::Json<UpdateUser>,
     pool: web::Data<PgPool>,
+    redis_pool: web::Data<deadpool_redis::Pool>,
 ) -> impl Responder {
     let user_id = path.into_inner();
     let updated_user = updated_user.into_inner();
 
+    if let Err(response) = validate_slot_interval(updated_user.slot_interval_minutes) {
+        return response;
+    }
+
+    if let Some(time_zone) = updated_user.time_zone.as_deref()
+        && let Err(response) = validate_time_zone(time_zone)
+    {
+        return response;
+    }
+
+    if let Err(response) = validate_booking_window(
+        updated_user.min_notice_minutes,
+        updated_user.max_advance_days,
+    ) {
+        return response;
+    }
+
     match sqlx::query_as!(
         User,
         r#"
@@ -238,8 +282,12 @@ pub async fn update_user(
             phone_number = COALESCE($5, phone_number),
             description = COALESCE($6, description),
             phone_number_is_whatsapp = COALESCE($7, phone_number_is_whatsapp),
+            slot_interval_minutes = COALESCE($8, slot_interval_minutes),
+            min_notice_minutes = COALESCE($9, min_notice_minutes),
+            max_advance_days = COALESCE($10, max_advance_days),
+            time_zone = COALESCE($11, time_zone),
             updated_at = NOW()
-        WHERE id = $8
+        WHERE id = $12
         RETURNING *
         "#,
         updated_user.username,
@@ -249,16 +297,25 @@ pub async fn update_user(
         updated_user.phone_number,
         updated_user.description,
         updated_user.phone_number_is_whatsapp,
+        updated_user.slot_interval_minutes,
+        updated_user.min_notice_minutes,
+        updated_user.max_advance_days,
+        updated_user.time_zone,
         user_id
     )
     .fetch_one(pool.get_ref())
     .await
     {
-        Ok(user) => HttpResponse::Ok().json(ApiResponse {
-            message: Some("User updated successfully".to_string()),
-            data: Some(user),
-            success: true,
-        }),
+        Ok(user) => {
+            // The slot interval shapes every cached day of the business
+            invalidate_all_business_slot_cache(&redis_pool, user_id).await;
+
+            HttpResponse::Ok().json(ApiResponse {
+                message: Some("User updated successfully".to_string()),
+                data: Some(user),
+                success: true,
+            })
+        }
 
         Err(sqlx::Error::RowNotFound) => not_found_response("User not found".to_string()),
 
@@ -288,7 +345,7 @@ pub async fn update_user(
     get,
     path = "/users/{id}/appointments",
     tag = "Users",
-    params(("id" = Uuid, Path, description = "User ID")),
+    params(("id" = Uuid, Path, description = "User ID"), AppointmentQuery),
     responses(
         (status = 200, body = ApiResponse<Vec<Appointment>>),
         (status = 400, description = "Bad Request"),
@@ -297,18 +354,22 @@ pub async fn update_user(
 )]
 pub async fn get_appointments_for_user(
     path: web::Path<Uuid>,
+    query: web::Query<AppointmentQuery>,
     pool: web::Data<PgPool>,
 ) -> impl Responder {
     let user_id = path.into_inner();
+    let status = query.status.map(|s| s.as_str());
 
     match sqlx::query_as!(
         Appointment,
         r#"
         SELECT * FROM appointments
         WHERE business_id = $1
+        AND ($2::VARCHAR IS NULL OR status = $2)
         ORDER BY appointment_start_time DESC
         "#,
-        user_id
+        user_id,
+        status
     )
     .fetch_all(pool.get_ref())
     .await
@@ -569,27 +630,6 @@ pub async fn get_available_slots(
 ) -> impl Responder {
     let user_id = path.into_inner();
     let date_str = &query.date;
-    let cache_key = format!("slots:{}:{}:{}", user_id, query.date, query.service_id);
-    let mut conn = redis_pool.get().await.unwrap();
-
-    // Try to get from cache first
-    let cached: Option<String> = redis::cmd("GET")
-        .arg(&cache_key)
-        .query_async(&mut conn)
-        .await
-        .unwrap_or(None);
-
-    if let Some(json_str) = cached {
-        let slots: Vec<TimeSlot> = serde_json::from_str(&json_str).unwrap();
-
-        println!("Cache hit for key: {}", cache_key);
-
-        return HttpResponse::Ok().json(ApiResponse {
-            success: true,
-            data: Some(slots),
-            message: None,
-        });
-    }
 
     let format = match format_description::parse("[year]-[month]-[day]") {
         Ok(f) => f,
@@ -604,43 +644,65 @@ pub async fn get_available_slots(
         Err(_) => return bad_request_response("Invalid date format (YYYY-MM-DD)".to_string()),
     };
 
-    let service = match sqlx::query_as!(
-        Service,
-        "SELECT * FROM services WHERE id = $1",
-        query.service_id
-    )
-    .fetch_one(pool.get_ref())
-    .await
-    {
-        Ok(s) => s,
-        Err(_) => return not_found_response("Service not found.".to_string()),
-    };
+    let (settings, (earliest_start, latest_start)) =
+        match load_slot_settings(&pool, user_id, query.service_id).await {
+            Ok(s) => s,
+            Err(response) => return response,
+        };
+
+    let cache_key = slot_cache_key(user_id, requested_date, query.service_id, query.staff_id);
+    let mut conn = redis_pool.get().await.unwrap();
 
-    let duration_minutes = service.duration_minutes.unwrap_or(30) as i64;
-    let weekday = requested_date.weekday().number_from_monday() as i32;
+    // Holds come and go within minutes, a day with any in progress is worked
+    // out fresh and kept out of the cache
+    let holds = business_holds(&redis_pool, user_id)
+        .await
+        .unwrap_or_default();
+    let day_is_held = holds
+        .iter()
+        .any(|hold| (hold.start_time.date() - requested_date).whole_days().abs() <= 1);
 
-    let rules = match sqlx::query_as!(
-        AvailabilityRule,
-        "SELECT * FROM business_availability WHERE user_id = $1 AND day_of_week = $2",
-        user_id,
-        weekday
-    )
-    .fetch_all(pool.get_ref())
-    .await
-    {
-        Ok(r) => r,
-        Err(e) => return internal_server_error_response(e.to_string()),
+    // Try to get from cache first
+    let cached: Option<String> = if day_is_held {
+        None
+    } else {
+        redis::cmd("GET")
+            .arg(&cache_key)
+            .query_async(&mut conn)
+            .await
+            .unwrap_or(None)
     };
 
-    if rules.is_empty() {
+    if let Some(json_str) = cached {
+        let mut slots: Vec<TimeSlot> = serde_json::from_str(&json_str).unwrap();
+        retain_bookable_slots(&mut slots, earliest_start, latest_start);
+
+        println!("Cache hit for key: {}", cache_key);
+
         return HttpResponse::Ok().json(ApiResponse {
             success: true,
-            data: Some(Vec::<TimeSlot>::new()),
-            message: Some("Closed this day".to_string()),
+            data: Some(slots),
+            message: None,
         });
     }
 
-    let tz: Tz = match Tz::from_str(&rules[0].time_zone) {
+    let mut db_conn = match pool.acquire().await {
+        Ok(c) => c,
+        Err(e) => return internal_server_error_response(e.to_string()),
+    };
+
+    let lanes =
+        match load_booking_lanes(&mut db_conn, user_id, query.service_id, query.staff_id).await {
+            Ok(l) => l,
+            Err(response) => return response,
+        };
+
+    let time_zone = match business_time_zone(&mut db_conn, user_id).await {
+        Ok(z) => z,
+        Err(e) => return internal_server_error_response(e.to_string()),
+    };
+
+    let tz: Tz = match Tz::from_str(&time_zone) {
         Ok(z) => z,
         Err(_) => return internal_server_error_response("Invalid DB Timezone".to_string()),
     };
@@ -653,101 +715,81 @@ pub async fn get_available_slots(
     let utc_window_start = local_to_utc(day_start_naive, &tz).unwrap();
     let utc_window_end = local_to_utc(day_end_naive, &tz).unwrap();
 
-    // We will collect ALL unavailable times (DB + Google) into this vector
-    let mut blocked_periods: Vec<(OffsetDateTime, OffsetDateTime)> = Vec::new();
+    let shared_periods = match collect_shared_blocked_periods(
+        &mut db_conn,
+        user_id,
+        query.service_id,
+        &tz,
+        (utc_window_start, utc_window_end),
+        &holds,
+    )
+    .await
+    {
+        Ok(p) => p,
+        Err(response) => return response,
+    };
 
-    let db_appointments = match sqlx::query_as!(
-        Appointment,
-        "SELECT * FROM appointments WHERE business_id = $1
-        AND appointment_end_time > $2
-        AND appointment_start_time < $3",
+    let schedule = match load_lane_schedule(
+        &mut db_conn,
         user_id,
-        utc_window_start,
-        utc_window_end
+        query.service_id,
+        &lanes,
+        (requested_date, requested_date),
     )
-    .fetch_all(pool.get_ref())
     .await
     {
-        Ok(a) => a,
-        Err(e) => return internal_server_error_response(e.to_string()),
+        Ok(s) => s,
+        Err(response) => return response,
     };
 
-    // Add DB appointments to blocked list
-    for appt in db_appointments {
-        blocked_periods.push((appt.appointment_start_time, appt.appointment_end_time));
-    }
+    let access_token = slot_access_token(&pool, config, &http_client, user_id).await;
 
-    let auth_record = sqlx::query_as!(Auth, "SELECT * FROM auth WHERE user_id = $1", user_id)
-        .fetch_optional(pool.get_ref())
-        .await
-        .unwrap_or(None);
+    let lanes = match collect_lane_blocked_periods(
+        &pool,
+        &http_client,
+        access_token.as_deref(),
+        user_id,
+        lanes,
+        &shared_periods,
+        (utc_window_start, utc_window_end),
+    )
+    .await
+    {
+        Ok(l) => l,
+        Err(response) => return response,
+    };
 
-    if let Some(auth) = auth_record {
-        if let Some(refresh_token) = auth.refresh_token {
-            if let Ok(access_token) =
-                get_new_access_token(config, &http_client, refresh_token).await
-            {
-                let freebusy_request = FreeBusyRequest {
-                    time_min: utc_window_start.format(&Rfc3339).unwrap(),
-                    time_max: utc_window_end.format(&Rfc3339).unwrap(),
-                    items: vec![FreeBusyRequestItem {
-                        id: "primary".to_string(),
-                    }],
-                };
-
-                let freebusy_response = http_client
-                    .post("https://www.googleapis.com/calendar/v3/freeBusy")
-                    .bearer_auth(access_token)
-                    .json(&freebusy_request)
-                    .send()
-                    .await;
-
-                if let Ok(res) = freebusy_response {
-                    if let Ok(parsed) = res.json::<FreeBusyResponse>().await {
-                        if let Some(calendar) = parsed.calendars.get("primary") {
-                            for busy_slot in &calendar.busy {
-                                let start = OffsetDateTime::parse(&busy_slot.start, &Rfc3339).ok();
-                                let end = OffsetDateTime::parse(&busy_slot.end, &Rfc3339).ok();
-
-                                if let (Some(s), Some(e)) = (start, end) {
-                                    blocked_periods.push((s, e));
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+    // Generate Slots And Check Collisions
+    let mut available_slots = match lane_slots_for_date(
+        &schedule,
+        query.service_id,
+        &lanes,
+        requested_date,
+        &settings,
+        &tz,
+    ) {
+        Some(slots) => slots,
+
+        // Overnight hours of the day before still open this one
+        None => {
+            return HttpResponse::Ok().json(ApiResponse {
+                success: true,
+                data: Some(Vec::<TimeSlot>::new()),
+                message: Some("Closed this day".to_string()),
+            });
         }
-    }
+    };
 
-    // Generate Slots And Check Collisions
-    let mut available_slots: Vec<TimeSlot> = Vec::new();
-
-    for rule in rules {
-        let current_open_naive = PrimitiveDateTime::new(requested_date, rule.open_time);
-        let current_close_naive = PrimitiveDateTime::new(requested_date, rule.close_time);
-        let mut generated_slots = generate_slots(
-            current_open_naive,
-            current_close_naive,
-            duration_minutes,
-            &tz,
-            &blocked_periods,
-        );
+    // Cache the result
+    if !day_is_held {
+        let json_response = serde_json::to_string(&available_slots).unwrap();
 
-        available_slots.append(&mut generated_slots);
+        cache_slots(&mut conn, user_id, &cache_key, json_response)
+            .await
+            .unwrap();
     }
 
-    // Cache the result
-    let json_response = serde_json::to_string(&available_slots).unwrap();
-
-    let _: () = redis::cmd("SET")
-        .arg(&cache_key)
-        .arg(json_response)
-        .arg("EX")
-        .arg(60 * 5)
-        .query_async(&mut conn)
-        .await
-        .unwrap();
+    retain_bookable_slots(&mut available_slots, earliest_start, latest_start);
 
     HttpResponse::Ok().json(ApiResponse {
         success: true,
@@ -767,98 +809,173 @@ pub async fn get_available_slots(
 /* -------------------------------------------------------------------------- */
 
 #[utoipa::path(
-    post,
-    path = "/users/me/availability",
+    get,
+    path = "/users/{id}/slots/range",
     tag = "Users",
-    request_body = SetAvailability,
+    params(("id" = Uuid, Path, description = "User ID"), SlotRangeQuery),
     responses(
-        (status = 200, body = ApiResponse<EmptyStruct>),
+        (status = 200, body = ApiResponse<Vec<DaySlots>>),
         (status = 400, description = "Bad Request"),
         (status = 500, description = "Internal Server Error")
     )
 )]
-pub async fn set_user_availability(
-    user: AuthenticatedUser,
+pub async fn get_available_slots_range(
+    path: web::Path<Uuid>,
+    query: web::Query<SlotRangeQuery>,
     pool: web::Data<PgPool>,
-    body: web::Json<SetAvailability>,
+    config: web::Data<Config>,
+    http_client: web::Data<reqwest::Client>,
+    redis_pool: web::Data<deadpool_redis::Pool>,
 ) -> impl Responder {
-    let user_id = user.user_id;
-    let rules = body.rules.iter();
+    let user_id = path.into_inner();
 
-    let mut tx = match pool.begin().await {
-        Ok(tx) => tx,
+    let format = match format_description::parse("[year]-[month]-[day]") {
+        Ok(f) => f,
+        Err(_) => {
+            return internal_server_error_response("Invalid date format in code!".to_string());
+        }
+    };
+
+    let (from_date, to_date) = match (
+        Date::parse(&query.from, &format),
+        Date::parse(&query.to, &format),
+    ) {
+        (Ok(from), Ok(to)) => (from, to),
+        _ => return bad_request_response("Invalid date format (YYYY-MM-DD)".to_string()),
+    };
+
+    if to_date < from_date {
+        return bad_request_response("`to` cannot be before `from`.".to_string());
+    }
+
+    let day_count = (to_date - from_date).whole_days() + 1;
+
+    if day_count > MAX_SLOT_RANGE_DAYS {
+        return bad_request_response(format!(
+            "Date range cannot exceed {} days.",
+            MAX_SLOT_RANGE_DAYS
+        ));
+    }
+
+    let (settings, (earliest_start, latest_start)) =
+        match load_slot_settings(&pool, user_id, query.service_id).await {
+            Ok(s) => s,
+            Err(response) => return response,
+        };
+
+    // A Redis hiccup only shows held slots as open, booking them still fails
+    let holds = business_holds(&redis_pool, user_id)
+        .await
+        .unwrap_or_default();
+
+    let mut db_conn = match pool.acquire().await {
+        Ok(c) => c,
         Err(e) => return internal_server_error_response(e.to_string()),
     };
 
-    // Delete all existing rules for this user
-    if let Err(e) = sqlx::query!(
-        "DELETE FROM business_availability WHERE user_id = $1",
-        user_id
+    let lanes =
+        match load_booking_lanes(&mut db_conn, user_id, query.service_id, query.staff_id).await {
+            Ok(l) => l,
+            Err(response) => return response,
+        };
+
+    let time_zone = match business_time_zone(&mut db_conn, user_id).await {
+        Ok(z) => z,
+        Err(e) => return internal_server_error_response(e.to_string()),
+    };
+
+    let tz: Tz = match Tz::from_str(&time_zone) {
+        Ok(z) => z,
+        Err(_) => return internal_server_error_response("Invalid DB Timezone".to_string()),
+    };
+
+    let Some(day_after_range) = to_date.next_day() else {
+        return bad_request_response("Date range is out of bounds.".to_string());
+    };
+
+    let range_start_naive = PrimitiveDateTime::new(from_date, Time::MIDNIGHT);
+    let range_end_naive = PrimitiveDateTime::new(day_after_range, Time::MIDNIGHT);
+
+    let (Some(utc_window_start), Some(utc_window_end)) = (
+        local_to_utc(range_start_naive, &tz),
+        local_to_utc(range_end_naive, &tz),
+    ) else {
+        return bad_request_response(
+            "Date range starts or ends at a skipped local time.".to_string(),
+        );
+    };
+
+    // One round of bookings and Google free/busy for the whole range
+    let shared_periods = match collect_shared_blocked_periods(
+        &mut db_conn,
+        user_id,
+        query.service_id,
+        &tz,
+        (utc_window_start, utc_window_end),
+        &holds,
     )
-    .execute(&mut *tx)
     .await
     {
-        tx.rollback().await.ok();
-        return internal_server_error_response(e.to_string());
-    }
+        Ok(p) => p,
+        Err(response) => return response,
+    };
 
-    let time_format = match time::format_description::parse("[hour]:[minute]:[second]") {
-        Ok(format) => format,
-        Err(_) => return internal_server_error_response("Internal time format error.".to_string()),
+    // Opening hours of the whole range in one round too
+    let schedule = match load_lane_schedule(
+        &mut db_conn,
+        user_id,
+        query.service_id,
+        &lanes,
+        (from_date, to_date),
+    )
+    .await
+    {
+        Ok(s) => s,
+        Err(response) => return response,
     };
 
-    // Insert the new rules
-    for slot in rules {
-        let open_time = match Time::parse(&slot.open_time, &time_format) {
-            Ok(t) => t,
-            Err(_) => {
-                return bad_request_response(format!(
-                    "Invalid open_time format for {}. Expected HH:MM:SS.",
-                    slot.open_time
-                ));
-            }
-        };
+    let access_token = slot_access_token(&pool, config, &http_client, user_id).await;
 
-        let close_time = match Time::parse(&slot.close_time, &time_format) {
-            Ok(t) => t,
-            Err(_) => {
-                return bad_request_response(format!(
-                    "Invalid close_time format for {}. Expected HH:MM:SS.",
-                    slot.close_time
-                ));
-            }
-        };
+    let lanes = match collect_lane_blocked_periods(
+        &pool,
+        &http_client,
+        access_token.as_deref(),
+        user_id,
+        lanes,
+        &shared_periods,
+        (utc_window_start, utc_window_end),
+    )
+    .await
+    {
+        Ok(l) => l,
+        Err(response) => return response,
+    };
 
-        if let Err(e) = sqlx::query!(
-           r#"
-            INSERT INTO business_availability (user_id, day_of_week, open_time, close_time, time_zone)
-            VALUES ($1, $2, $3, $4, $5)
-            "#,
-            user_id,
-            slot.day_of_week,
-            open_time,
-            close_time,
-            slot.time_zone
-        )
-        .execute(&mut *tx)
-        .await
-        {
-            tx.rollback().await.ok();
-            return internal_server_error_response(e.to_string());
-        }
-    }
+    let mut days: Vec<DaySlots> = Vec::new();
+    let mut date = from_date;
 
-    if let Err(e) = tx.commit().await {
-        return internal_server_error_response(e.to_string());
+    while date < day_after_range {
+        let mut slots =
+            lane_slots_for_date(&schedule, query.service_id, &lanes, date, &settings, &tz)
+                .unwrap_or_default();
+        retain_bookable_slots(&mut slots, earliest_start, latest_start);
+
+        days.push(DaySlots {
+            date: date.to_string(),
+            slots,
+        });
+
+        date = match date.next_day() {
+            Some(d) => d,
+            None => break,
+        };
     }
 
-    let response = ApiResponse::<()> {
+    HttpResponse::Ok().json(ApiResponse {
         success: true,
-        data: None,
-        message: Some("Availability schedule updated successfully.".to_string()),
-    };
-
-    HttpResponse::Ok().json(response)
+        data: Some(days),
+        message: None,
+    })
 }
 
 /* -------------------------------------------------------------------------- */
@@ -871,15 +988,1320 @@ pub async fn set_user_availability(
 /*                                      -                                     */
 /* -------------------------------------------------------------------------- */
 
-pub fn user_config(cfg: &mut web::ServiceConfig) {
-    cfg.service(
-        web::scope("/users")
-            .route("/me", web::get().to(get_me))
-            .route("/me/upload-url", web::get().to(get_user_upload_url))
-            .route("/me/status", web::patch().to(set_account_status))
-            .route("/me/availability", web::post().to(set_user_availability))
-            .route("/with-services", web::get().to(get_all_users_with_services))
-            .route("/{id}/slots", web::get().to(get_available_slots))
+#[utoipa::path(
+    get,
+    path = "/users/{id}/slots/next",
+    tag = "Users",
+    params(("id" = Uuid, Path, description = "User ID"), NextSlotQuery),
+    responses(
+        (status = 200, body = ApiResponse<Vec<TimeSlot>>),
+        (status = 400, description = "Bad Request"),
+        (status = 500, description = "Internal Server Error")
+    )
+)]
+pub async fn get_next_available_slots(
+    path: web::Path<Uuid>,
+    query: web::Query<NextSlotQuery>,
+    pool: web::Data<PgPool>,
+    config: web::Data<Config>,
+    http_client: web::Data<reqwest::Client>,
+    redis_pool: web::Data<deadpool_redis::Pool>,
+) -> impl Responder {
+    let user_id = path.into_inner();
+    let limit = query.limit.unwrap_or(1);
+
+    if !(1..=MAX_NEXT_SLOTS).contains(&limit) {
+        return bad_request_response(format!("limit must be between 1 and {}.", MAX_NEXT_SLOTS));
+    }
+
+    let (settings, (earliest_start, latest_start)) =
+        match load_slot_settings(&pool, user_id, query.service_id).await {
+            Ok(s) => s,
+            Err(response) => return response,
+        };
+
+    // A Redis hiccup only shows held slots as open, booking them still fails
+    let holds = business_holds(&redis_pool, user_id)
+        .await
+        .unwrap_or_default();
+
+    let mut db_conn = match pool.acquire().await {
+        Ok(c) => c,
+        Err(e) => return internal_server_error_response(e.to_string()),
+    };
+
+    let lanes =
+        match load_booking_lanes(&mut db_conn, user_id, query.service_id, query.staff_id).await {
+            Ok(l) => l,
+            Err(response) => return response,
+        };
+
+    let time_zone = match business_time_zone(&mut db_conn, user_id).await {
+        Ok(z) => z,
+        Err(e) => return internal_server_error_response(e.to_string()),
+    };
+
+    let tz: Tz = match Tz::from_str(&time_zone) {
+        Ok(z) => z,
+        Err(_) => return internal_server_error_response("Invalid DB Timezone".to_string()),
+    };
+
+    let today = match convert_to_local_primitive(OffsetDateTime::now_utc(), &time_zone) {
+        Ok(now) => now.date(),
+        Err(e) => return internal_server_error_response(e),
+    };
+
+    // Never look further than the business lets customers book
+    let last_date = match convert_to_local_primitive(latest_start, &time_zone) {
+        Ok(latest) => latest
+            .date()
+            .min(today + time::Duration::days(MAX_NEXT_SLOT_LOOKAHEAD_DAYS - 1)),
+        Err(e) => return internal_server_error_response(e),
+    };
+
+    let schedule = match load_lane_schedule(
+        &mut db_conn,
+        user_id,
+        query.service_id,
+        &lanes,
+        (today, last_date),
+    )
+    .await
+    {
+        Ok(s) => s,
+        Err(response) => return response,
+    };
+
+    let access_token = slot_access_token(&pool, config, &http_client, user_id).await;
+
+    let mut found: Vec<TimeSlot> = Vec::new();
+    let mut chunk_start = today;
+
+    // Bookings and Google busy time are fetched a chunk of days at a time,
+    // so an early opening costs a single round trip
+    while chunk_start <= last_date && found.len() < limit {
+        let chunk_end =
+            (chunk_start + time::Duration::days(NEXT_SLOT_CHUNK_DAYS - 1)).min(last_date);
+
+        let Some(day_after_chunk) = chunk_end.next_day() else {
+            break;
+        };
+
+        let chunk_start_naive = PrimitiveDateTime::new(chunk_start, Time::MIDNIGHT);
+        let chunk_end_naive = PrimitiveDateTime::new(day_after_chunk, Time::MIDNIGHT);
+
+        let (Some(utc_window_start), Some(utc_window_end)) = (
+            local_to_utc(chunk_start_naive, &tz),
+            local_to_utc(chunk_end_naive, &tz),
+        ) else {
+            return internal_server_error_response(
+                "Search window starts or ends at a skipped local time.".to_string(),
+            );
+        };
+
+        let shared_periods = match collect_shared_blocked_periods(
+            &mut db_conn,
+            user_id,
+            query.service_id,
+            &tz,
+            (utc_window_start, utc_window_end),
+            &holds,
+        )
+        .await
+        {
+            Ok(p) => p,
+            Err(response) => return response,
+        };
+
+        let chunk_lanes = match collect_lane_blocked_periods(
+            &pool,
+            &http_client,
+            access_token.as_deref(),
+            user_id,
+            lanes.clone(),
+            &shared_periods,
+            (utc_window_start, utc_window_end),
+        )
+        .await
+        {
+            Ok(l) => l,
+            Err(response) => return response,
+        };
+
+        let mut date = chunk_start;
+
+        while date < day_after_chunk && found.len() < limit {
+            let mut slots = lane_slots_for_date(
+                &schedule,
+                query.service_id,
+                &chunk_lanes,
+                date,
+                &settings,
+                &tz,
+            )
+            .unwrap_or_default();
+            retain_bookable_slots(&mut slots, earliest_start, latest_start);
+
+            found.extend(slots.into_iter().take(limit - found.len()));
+
+            date = match date.next_day() {
+                Some(d) => d,
+                None => break,
+            };
+        }
+
+        chunk_start = day_after_chunk;
+    }
+
+    let message = found
+        .is_empty()
+        .then(|| "No openings found in the search window".to_string());
+
+    HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        data: Some(found),
+        message,
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+#[utoipa::path(
+    post,
+    path = "/users/me/availability",
+    tag = "Users",
+    request_body = SetAvailability,
+    responses(
+        (status = 200, body = ApiResponse<EmptyStruct>),
+        (status = 400, body = ApiResponse<Vec<AvailabilityRuleError>>, description = "Invalid Rules"),
+        (status = 500, description = "Internal Server Error")
+    )
+)]
+pub async fn set_user_availability(
+    user: AuthenticatedUser,
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<deadpool_redis::Pool>,
+    body: web::Json<SetAvailability>,
+) -> impl Responder {
+    let user_id = user.user_id;
+
+    // Everything is checked before the current schedule is touched
+    let rules = match validate_weekly_rules(&body.rules) {
+        Ok(r) => r,
+        Err(errors) => {
+            return HttpResponse::BadRequest().json(ApiResponse {
+                success: false,
+                data: Some(errors),
+                message: Some("Invalid availability rules.".to_string()),
+            });
+        }
+    };
+
+    let time_zone = match requested_time_zone(&body) {
+        Ok(z) => z,
+        Err(response) => return response,
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return internal_server_error_response(e.to_string()),
+    };
+
+    // Delete all existing rules for this user
+    if let Err(e) = sqlx::query!(
+        "DELETE FROM business_availability WHERE user_id = $1",
+        user_id
+    )
+    .execute(&mut *tx)
+    .await
+    {
+        tx.rollback().await.ok();
+        return internal_server_error_response(e.to_string());
+    }
+
+    // Insert the new rules, overlapping windows already merged
+    for (day_of_week, open_time, close_time) in rules {
+        if let Err(e) = sqlx::query!(
+            r#"
+            INSERT INTO business_availability (user_id, day_of_week, open_time, close_time)
+            VALUES ($1, $2, $3, $4)
+            "#,
+            user_id,
+            day_of_week,
+            open_time,
+            close_time
+        )
+        .execute(&mut *tx)
+        .await
+        {
+            tx.rollback().await.ok();
+            return internal_server_error_response(e.to_string());
+        }
+    }
+
+    if let Some(time_zone) = time_zone
+        && let Err(e) = sqlx::query!(
+            "UPDATE users SET time_zone = $1, updated_at = NOW() WHERE id = $2",
+            time_zone,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await
+    {
+        tx.rollback().await.ok();
+        return internal_server_error_response(e.to_string());
+    }
+
+    if let Err(e) = tx.commit().await {
+        return internal_server_error_response(e.to_string());
+    }
+
+    // A new time zone moves every cached slot, new weekly hours change them
+    invalidate_all_business_slot_cache(&redis_pool, user_id).await;
+
+    let response = ApiResponse::<()> {
+        success: true,
+        data: None,
+        message: Some("Availability schedule updated successfully.".to_string()),
+    };
+
+    HttpResponse::Ok().json(response)
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+#[utoipa::path(
+    get,
+    path = "/users/me/breaks",
+    tag = "Users",
+    responses(
+        (status = 200, body = ApiResponse<Vec<BusinessBreak>>),
+        (status = 500, description = "Internal Server Error")
+    )
+)]
+pub async fn get_breaks(user: AuthenticatedUser, pool: web::Data<PgPool>) -> impl Responder {
+    match sqlx::query_as!(
+        BusinessBreak,
+        r#"
+        SELECT * FROM business_breaks
+        WHERE user_id = $1
+        ORDER BY day_of_week, start_time
+        "#,
+        user.user_id
+    )
+    .fetch_all(pool.get_ref())
+    .await
+    {
+        Ok(breaks) => HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(breaks),
+            message: None,
+        }),
+
+        Err(e) => internal_server_error_response(e.to_string()),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+#[utoipa::path(
+    post,
+    path = "/users/me/breaks",
+    tag = "Users",
+    request_body = SetBreaks,
+    responses(
+        (status = 200, body = ApiResponse<Vec<BusinessBreak>>),
+        (status = 400, description = "Bad Request"),
+        (status = 500, description = "Internal Server Error")
+    )
+)]
+pub async fn set_breaks(
+    user: AuthenticatedUser,
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<deadpool_redis::Pool>,
+    body: web::Json<SetBreaks>,
+) -> impl Responder {
+    let user_id = user.user_id;
+
+    let windows = match parse_break_windows(&body) {
+        Ok(w) => w,
+        Err(response) => return response,
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return internal_server_error_response(e.to_string()),
+    };
+
+    // The posted list replaces every existing break
+    if let Err(e) = sqlx::query!("DELETE FROM business_breaks WHERE user_id = $1", user_id)
+        .execute(&mut *tx)
+        .await
+    {
+        tx.rollback().await.ok();
+        return internal_server_error_response(e.to_string());
+    }
+
+    let mut breaks = Vec::new();
+
+    for (day_of_week, start_time, end_time) in windows {
+        match sqlx::query_as!(
+            BusinessBreak,
+            r#"
+            INSERT INTO business_breaks (user_id, day_of_week, start_time, end_time)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+            "#,
+            user_id,
+            day_of_week,
+            start_time,
+            end_time
+        )
+        .fetch_one(&mut *tx)
+        .await
+        {
+            Ok(b) => breaks.push(b),
+            Err(e) => {
+                tx.rollback().await.ok();
+                return internal_server_error_response(e.to_string());
+            }
+        }
+    }
+
+    if let Err(e) = tx.commit().await {
+        return internal_server_error_response(e.to_string());
+    }
+
+    invalidate_all_business_slot_cache(&redis_pool, user_id).await;
+
+    HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        data: Some(breaks),
+        message: Some("Breaks updated successfully.".to_string()),
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+#[utoipa::path(
+    get,
+    path = "/users/me/availability/overrides",
+    tag = "Users",
+    responses(
+        (status = 200, body = ApiResponse<Vec<AvailabilityOverride>>),
+        (status = 500, description = "Internal Server Error")
+    )
+)]
+pub async fn get_availability_overrides(
+    user: AuthenticatedUser,
+    pool: web::Data<PgPool>,
+) -> impl Responder {
+    match sqlx::query_as!(
+        AvailabilityOverride,
+        r#"
+        SELECT * FROM availability_overrides
+        WHERE user_id = $1
+        ORDER BY override_date, open_time
+        "#,
+        user.user_id
+    )
+    .fetch_all(pool.get_ref())
+    .await
+    {
+        Ok(overrides) => HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(overrides),
+            message: None,
+        }),
+
+        Err(e) => internal_server_error_response(e.to_string()),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+#[utoipa::path(
+    post,
+    path = "/users/me/availability/overrides",
+    tag = "Users",
+    request_body = CreateAvailabilityOverride,
+    responses(
+        (status = 201, body = ApiResponse<Vec<AvailabilityOverride>>),
+        (status = 400, description = "Bad Request"),
+        (status = 409, description = "Date Already Overridden"),
+        (status = 500, description = "Internal Server Error")
+    )
+)]
+pub async fn create_availability_override(
+    user: AuthenticatedUser,
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<deadpool_redis::Pool>,
+    body: web::Json<CreateAvailabilityOverride>,
+) -> impl Responder {
+    let user_id = user.user_id;
+    let new_override = body.into_inner();
+
+    let override_date = match parse_override_date(&new_override.date) {
+        Ok(d) => d,
+        Err(response) => return response,
+    };
+
+    let windows = match parse_override_windows(&new_override.hours) {
+        Ok(w) => w,
+        Err(response) => return response,
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return internal_server_error_response(e.to_string()),
+    };
+
+    // Concurrent writes for the same date would each pass the check below
+    if let Err(e) = lock_business(&mut tx, user_id).await {
+        tx.rollback().await.ok();
+        return internal_server_error_response(e.to_string());
+    }
+
+    let exists = match sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM availability_overrides
+            WHERE user_id = $1 AND override_date = $2
+        ) as "exists!: bool"
+        "#,
+        user_id,
+        override_date
+    )
+    .fetch_one(&mut *tx)
+    .await
+    {
+        Ok(e) => e,
+        Err(e) => {
+            tx.rollback().await.ok();
+            return internal_server_error_response(e.to_string());
+        }
+    };
+
+    if exists {
+        tx.rollback().await.ok();
+
+        return conflict_reponse(format!(
+            "An override already exists for {}. Update it instead.",
+            new_override.date
+        ));
+    }
+
+    let overrides = match insert_override_rows(&mut tx, user_id, override_date, &windows).await {
+        Ok(o) => o,
+        Err(e) => {
+            tx.rollback().await.ok();
+            return internal_server_error_response(e.to_string());
+        }
+    };
+
+    if let Err(e) = tx.commit().await {
+        return internal_server_error_response(e.to_string());
+    }
+
+    invalidate_business_slot_cache(&redis_pool, user_id, &[override_date]).await;
+
+    HttpResponse::Created().json(ApiResponse {
+        success: true,
+        data: Some(overrides),
+        message: Some("Availability override created successfully.".to_string()),
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+#[utoipa::path(
+    put,
+    path = "/users/me/availability/overrides/{date}",
+    tag = "Users",
+    params(("date" = String, Path, description = "Date in YYYY-MM-DD")),
+    request_body = SetAvailabilityOverride,
+    responses(
+        (status = 200, body = ApiResponse<Vec<AvailabilityOverride>>),
+        (status = 400, description = "Bad Request"),
+        (status = 404, description = "Override Not Found"),
+        (status = 500, description = "Internal Server Error")
+    )
+)]
+pub async fn update_availability_override(
+    path: web::Path<String>,
+    user: AuthenticatedUser,
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<deadpool_redis::Pool>,
+    body: web::Json<SetAvailabilityOverride>,
+) -> impl Responder {
+    let user_id = user.user_id;
+
+    let override_date = match parse_override_date(&path.into_inner()) {
+        Ok(d) => d,
+        Err(response) => return response,
+    };
+
+    let windows = match parse_override_windows(&body) {
+        Ok(w) => w,
+        Err(response) => return response,
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return internal_server_error_response(e.to_string()),
+    };
+
+    if let Err(e) = lock_business(&mut tx, user_id).await {
+        tx.rollback().await.ok();
+        return internal_server_error_response(e.to_string());
+    }
+
+    // Replace whatever the date had before
+    let deleted = match sqlx::query!(
+        "DELETE FROM availability_overrides WHERE user_id = $1 AND override_date = $2",
+        user_id,
+        override_date
+    )
+    .execute(&mut *tx)
+    .await
+    {
+        Ok(result) => result.rows_affected(),
+        Err(e) => {
+            tx.rollback().await.ok();
+            return internal_server_error_response(e.to_string());
+        }
+    };
+
+    if deleted == 0 {
+        tx.rollback().await.ok();
+        return not_found_response("No override exists for this date.".to_string());
+    }
+
+    let overrides = match insert_override_rows(&mut tx, user_id, override_date, &windows).await {
+        Ok(o) => o,
+        Err(e) => {
+            tx.rollback().await.ok();
+            return internal_server_error_response(e.to_string());
+        }
+    };
+
+    if let Err(e) = tx.commit().await {
+        return internal_server_error_response(e.to_string());
+    }
+
+    invalidate_business_slot_cache(&redis_pool, user_id, &[override_date]).await;
+
+    HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        data: Some(overrides),
+        message: Some("Availability override updated successfully.".to_string()),
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+#[utoipa::path(
+    delete,
+    path = "/users/me/availability/overrides/{date}",
+    tag = "Users",
+    params(("date" = String, Path, description = "Date in YYYY-MM-DD")),
+    responses(
+        (status = 200, body = ApiResponse<EmptyStruct>),
+        (status = 400, description = "Bad Request"),
+        (status = 404, description = "Override Not Found"),
+        (status = 500, description = "Internal Server Error")
+    )
+)]
+pub async fn delete_availability_override(
+    path: web::Path<String>,
+    user: AuthenticatedUser,
+    pool: web::Data<PgPool>,
+    redis_pool: web::Data<deadpool_redis::Pool>,
+) -> impl Responder {
+    let user_id = user.user_id;
+
+    let override_date = match parse_override_date(&path.into_inner()) {
+        Ok(d) => d,
+        Err(response) => return response,
+    };
+
+    match sqlx::query!(
+        "DELETE FROM availability_overrides WHERE user_id = $1 AND override_date = $2",
+        user_id,
+        override_date
+    )
+    .execute(pool.get_ref())
+    .await
+    {
+        Ok(result) if result.rows_affected() == 0 => {
+            not_found_response("No override exists for this date.".to_string())
+        }
+
+        Ok(_) => {
+            // The weekly rules apply to this date again
+            invalidate_business_slot_cache(&redis_pool, user_id, &[override_date]).await;
+
+            HttpResponse::Ok().json(ApiResponse::<()> {
+                success: true,
+                data: None,
+                message: Some("Availability override deleted successfully.".to_string()),
+            })
+        }
+
+        Err(e) => internal_server_error_response(e.to_string()),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+// One zone for the whole business, older clients still send it on every rule
+fn requested_time_zone(body: &SetAvailability) -> Result<Option<String>, HttpResponse> {
+    let mut zones = body
+        .time_zone
+        .iter()
+        .chain(body.rules.iter().filter_map(|rule| rule.time_zone.as_ref()));
+
+    let Some(time_zone) = zones.next() else {
+        return Ok(None);
+    };
+
+    if zones.any(|other| other != time_zone) {
+        return Err(bad_request_response(
+            "All availability rules must use the same time zone.".to_string(),
+        ));
+    }
+
+    validate_time_zone(time_zone)?;
+
+    Ok(Some(time_zone.clone()))
+}
+
+fn parse_override_date(date: &str) -> Result<Date, HttpResponse> {
+    let format = format_description::parse("[year]-[month]-[day]")
+        .map_err(|_| internal_server_error_response("Invalid date format in code!".to_string()))?;
+
+    Date::parse(date, &format)
+        .map_err(|_| bad_request_response("Invalid date format (YYYY-MM-DD)".to_string()))
+}
+
+// An empty list means the whole date is closed
+fn parse_override_windows(
+    hours: &SetAvailabilityOverride,
+) -> Result<Vec<(Time, Time)>, HttpResponse> {
+    if hours.is_closed {
+        if !hours.windows.is_empty() {
+            return Err(bad_request_response(
+                "A closed day cannot have opening windows.".to_string(),
+            ));
+        }
+
+        return Ok(Vec::new());
+    }
+
+    if hours.windows.is_empty() {
+        return Err(bad_request_response(
+            "Provide at least one opening window or close the day.".to_string(),
+        ));
+    }
+
+    let time_format = format_description::parse("[hour]:[minute]:[second]")
+        .map_err(|_| internal_server_error_response("Internal time format error.".to_string()))?;
+
+    let mut windows = Vec::new();
+
+    for window in &hours.windows {
+        let open_time = Time::parse(&window.open_time, &time_format).map_err(|_| {
+            bad_request_response(format!(
+                "Invalid open_time format for {}. Expected HH:MM:SS.",
+                window.open_time
+            ))
+        })?;
+
+        let close_time = Time::parse(&window.close_time, &time_format).map_err(|_| {
+            bad_request_response(format!(
+                "Invalid close_time format for {}. Expected HH:MM:SS.",
+                window.close_time
+            ))
+        })?;
+
+        // An earlier close_time means the window runs past midnight
+        if close_time == open_time {
+            return Err(bad_request_response(format!(
+                "open_time and close_time cannot both be {}.",
+                window.open_time
+            )));
+        }
+
+        windows.push((open_time, close_time));
+    }
+
+    if override_windows_overlap(&windows) {
+        return Err(bad_request_response(
+            "Override windows cannot overlap.".to_string(),
+        ));
+    }
+
+    Ok(windows)
+}
+
+// Breaks on the same day may not overlap each other
+fn parse_break_windows(body: &SetBreaks) -> Result<Vec<(i32, Time, Time)>, HttpResponse> {
+    let time_format = format_description::parse("[hour]:[minute]:[second]")
+        .map_err(|_| internal_server_error_response("Internal time format error.".to_string()))?;
+
+    let mut windows = Vec::new();
+
+    for window in &body.breaks {
+        if !(1..=7).contains(&window.day_of_week) {
+            return Err(bad_request_response(format!(
+                "Invalid day_of_week {}. Expected 1 (Monday) to 7 (Sunday).",
+                window.day_of_week
+            )));
+        }
+
+        let start_time = Time::parse(&window.start_time, &time_format).map_err(|_| {
+            bad_request_response(format!(
+                "Invalid start_time format for {}. Expected HH:MM:SS.",
+                window.start_time
+            ))
+        })?;
+
+        let end_time = Time::parse(&window.end_time, &time_format).map_err(|_| {
+            bad_request_response(format!(
+                "Invalid end_time format for {}. Expected HH:MM:SS.",
+                window.end_time
+            ))
+        })?;
+
+        if start_time >= end_time {
+            return Err(bad_request_response(format!(
+                "Break starting at {} must end later the same day.",
+                window.start_time
+            )));
+        }
+
+        windows.push((window.day_of_week, start_time, end_time));
+    }
+
+    windows.sort();
+
+    if windows
+        .windows(2)
+        .any(|pair| pair[0].0 == pair[1].0 && pair[1].1 < pair[0].2)
+    {
+        return Err(bad_request_response(
+            "Breaks on the same day cannot overlap.".to_string(),
+        ));
+    }
+
+    Ok(windows)
+}
+
+// Override writes of a business go one at a time
+async fn lock_business(
+    tx: &mut Transaction<'_, Postgres>,
+    user_id: Uuid,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(r#"SELECT id FROM users WHERE id = $1 FOR UPDATE"#, user_id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+    Ok(())
+}
+
+async fn insert_override_rows(
+    tx: &mut Transaction<'_, Postgres>,
+    user_id: Uuid,
+    override_date: Date,
+    windows: &[(Time, Time)],
+) -> Result<Vec<AvailabilityOverride>, sqlx::Error> {
+    // A single row without times marks the date as closed
+    let rows: Vec<(Option<Time>, Option<Time>)> = if windows.is_empty() {
+        vec![(None, None)]
+    } else {
+        windows
+            .iter()
+            .map(|(open, close)| (Some(*open), Some(*close)))
+            .collect()
+    };
+
+    let mut overrides = Vec::new();
+
+    for (open_time, close_time) in rows {
+        let row = sqlx::query_as!(
+            AvailabilityOverride,
+            r#"
+            INSERT INTO availability_overrides (user_id, override_date, open_time, close_time)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+            "#,
+            user_id,
+            override_date,
+            open_time,
+            close_time
+        )
+        .fetch_one(&mut **tx)
+        .await?;
+
+        overrides.push(row);
+    }
+
+    Ok(overrides)
+}
+
+// Service settings with business fallbacks, plus the window customers may book in
+async fn load_slot_settings(
+    pool: &PgPool,
+    user_id: Uuid,
+    service_id: Uuid,
+) -> Result<(SlotSettings, (OffsetDateTime, OffsetDateTime)), HttpResponse> {
+    let service = sqlx::query_as!(Service, "SELECT * FROM services WHERE id = $1", service_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|_| not_found_response("Service not found.".to_string()))?;
+
+    let business = match sqlx::query!(
+        "SELECT slot_interval_minutes, min_notice_minutes, max_advance_days FROM users WHERE id = $1",
+        user_id
+    )
+    .fetch_one(pool)
+    .await
+    {
+        Ok(b) => b,
+        Err(sqlx::Error::RowNotFound) => {
+            return Err(not_found_response("User not found.".to_string()));
+        }
+        Err(e) => return Err(internal_server_error_response(e.to_string())),
+    };
+
+    // Too-soon and too-far slots are dropped on every read, cached or not
+    let bookable_window = booking_window(
+        OffsetDateTime::now_utc(),
+        service
+            .min_notice_minutes
+            .unwrap_or(business.min_notice_minutes) as i64,
+        service
+            .max_advance_days
+            .unwrap_or(business.max_advance_days) as i64,
+    );
+
+    let settings = SlotSettings {
+        duration: service.duration_minutes.unwrap_or(30) as i64,
+        interval: service
+            .slot_interval_minutes
+            .unwrap_or(business.slot_interval_minutes) as i64,
+        buffer_before: service.buffer_before_minutes as i64,
+        buffer_after: service.buffer_after_minutes as i64,
+        capacity: service.capacity,
+    };
+
+    Ok((settings, bookable_window))
+}
+
+// The lanes a slot search covers, only the requested staff member if any
+async fn load_booking_lanes(
+    db_conn: &mut PgConnection,
+    user_id: Uuid,
+    service_id: Uuid,
+    staff_id: Option<Uuid>,
+) -> Result<Vec<Option<Staff>>, HttpResponse> {
+    let mut lanes = booking_lanes(db_conn, user_id, service_id)
+        .await
+        .map_err(|e| internal_server_error_response(e.to_string()))?;
+
+    if let Some(staff_id) = staff_id {
+        lanes.retain(|lane| lane.as_ref().is_some_and(|staff| staff.id == staff_id));
+
+        if lanes.is_empty() {
+            return Err(bad_request_response(
+                "This staff member does not offer the service.".to_string(),
+            ));
+        }
+    }
+
+    Ok(lanes)
+}
+
+// Breaks and fully reserved resources, held ones included, stop every lane
+// alike. Holds in the window are handed to the lanes they were taken on too.
+async fn collect_shared_blocked_periods(
+    db_conn: &mut PgConnection,
+    user_id: Uuid,
+    service_id: Uuid,
+    tz: &Tz,
+    (utc_window_start, utc_window_end): (OffsetDateTime, OffsetDateTime),
+    holds: &[SlotHold],
+) -> Result<SharedBlockedPeriods, HttpResponse> {
+    let breaks = breaks_between(&mut *db_conn, user_id, utc_window_start, utc_window_end, tz)
+        .await
+        .map_err(|e| internal_server_error_response(e.to_string()))?;
+
+    let reserved = resource_blocked_periods(
+        db_conn,
+        service_id,
+        (utc_window_start, utc_window_end),
+        None,
+        holds,
+    )
+    .await
+    .map_err(|e| internal_server_error_response(e.to_string()))?;
+
+    let holds = holds
+        .iter()
+        .filter(|hold| hold.blocked_end > utc_window_start && hold.blocked_start < utc_window_end)
+        .cloned()
+        .collect();
+
+    Ok(SharedBlockedPeriods {
+        breaks,
+        reserved,
+        holds,
+    })
+}
+
+async fn collect_lane_blocked_periods(
+    pool: &PgPool,
+    http_client: &reqwest::Client,
+    access_token: Option<&str>,
+    user_id: Uuid,
+    lanes: Vec<Option<Staff>>,
+    shared_periods: &SharedBlockedPeriods,
+    utc_window: (OffsetDateTime, OffsetDateTime),
+) -> Result<Vec<BookingLane>, HttpResponse> {
+    let mut booking_lanes = Vec::new();
+
+    for staff in lanes {
+        let (mut blocked_periods, mut sessions) = collect_blocked_periods(
+            pool,
+            http_client,
+            access_token,
+            user_id,
+            staff.as_ref(),
+            utc_window,
+        )
+        .await?;
+
+        let staff_id = staff.as_ref().map(|staff| staff.id);
+
+        blocked_periods.extend_from_slice(&shared_periods.breaks);
+
+        // Checkouts in progress count like bookings of the lane
+        sessions.extend(hold_sessions(&shared_periods.holds, staff_id));
+        blocked_periods.extend(cross_lane_hold_periods(&shared_periods.holds, staff_id));
+
+        booking_lanes.push(BookingLane {
+            staff,
+            blocked_periods,
+            reserved_periods: shared_periods.reserved.clone(),
+            sessions,
+        });
+    }
+
+    Ok(booking_lanes)
+}
+
+// Openings of one local date over every lane, `None` when none of them works
+// that day at all
+fn lane_slots_for_date(
+    schedule: &OpeningSchedule,
+    service_id: Uuid,
+    lanes: &[BookingLane],
+    date: Date,
+    settings: &SlotSettings,
+    tz: &Tz,
+) -> Option<Vec<TimeSlot>> {
+    let mut slots = Vec::new();
+    let mut is_open = false;
+
+    for lane in lanes {
+        let staff_id = lane.staff.as_ref().map(|staff| staff.id);
+
+        // Date overrides take precedence over the weekly rules, the service and
+        // the staff member may only be available for part of that time
+        let (opening_hours, previous_day_hours) = schedule_hours(schedule, staff_id, date);
+
+        if opening_hours.is_empty() && previous_day_hours.is_empty() {
+            continue;
+        }
+
+        is_open = true;
+
+        let mut lane_slots = generate_day_slots(
+            date,
+            &opening_hours,
+            &previous_day_hours,
+            settings,
+            tz,
+            &lane.blocked_periods,
+        );
+
+        apply_group_sessions(
+            &mut lane_slots,
+            &lane.sessions,
+            &lane.reserved_periods,
+            service_id,
+            settings,
+        );
+        slots.append(&mut lane_slots);
+    }
+
+    merge_lane_slots(&mut slots);
+
+    is_open.then_some(slots)
+}
+
+// Opening hours of every lane from `from` to `to`
+async fn load_lane_schedule(
+    db_conn: &mut PgConnection,
+    user_id: Uuid,
+    service_id: Uuid,
+    lanes: &[Option<Staff>],
+    range: (Date, Date),
+) -> Result<OpeningSchedule, HttpResponse> {
+    let staff_ids: Vec<Uuid> = lanes.iter().flatten().map(|staff| staff.id).collect();
+
+    load_opening_schedule(db_conn, user_id, service_id, &staff_ids, range)
+        .await
+        .map_err(|e| internal_server_error_response(e.to_string()))
+}
+
+// Every unavailable period of one lane in the UTC window, bookings and Google
+// busy time alike. Group sessions come apart, their seats may still be free.
+async fn collect_blocked_periods(
+    pool: &PgPool,
+    http_client: &reqwest::Client,
+    access_token: Option<&str>,
+    user_id: Uuid,
+    staff: Option<&Staff>,
+    (utc_window_start, utc_window_end): (OffsetDateTime, OffsetDateTime),
+) -> Result<(Vec<(OffsetDateTime, OffsetDateTime)>, Vec<GroupSession>), HttpResponse> {
+    let mut blocked_periods: Vec<(OffsetDateTime, OffsetDateTime)> = Vec::new();
+
+    // Existing bookings block their own prep and cleanup time too, sessions of
+    // a colliding lane have no seat to offer here
+    let db_appointments = sqlx::query!(
+        r#"
+        SELECT
+            a.appointment_start_time - make_interval(mins => s.buffer_before_minutes) as "blocked_start!",
+            a.appointment_end_time + make_interval(mins => s.buffer_after_minutes) as "blocked_end!"
+        FROM appointments a
+        JOIN services s ON s.id = a.service_id
+        WHERE a.business_id = $1
+        AND ($4::UUID IS NULL OR a.staff_id IS NULL OR a.staff_id = $4)
+        AND a.appointment_end_time + make_interval(mins => s.buffer_after_minutes) > $2
+        AND a.appointment_start_time - make_interval(mins => s.buffer_before_minutes) < $3
+        AND a.status IN ('pending', 'confirmed')
+        AND (a.session_id IS NULL OR a.staff_id IS DISTINCT FROM $4)
+        "#,
+        user_id,
+        utc_window_start,
+        utc_window_end,
+        staff.map(|staff| staff.id)
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| internal_server_error_response(e.to_string()))?;
+
+    for appt in db_appointments {
+        blocked_periods.push((appt.blocked_start, appt.blocked_end));
+    }
+
+    let sessions: Vec<GroupSession> = sqlx::query!(
+        r#"
+        SELECT
+            a.service_id,
+            a.appointment_start_time,
+            a.appointment_end_time,
+            a.appointment_start_time - make_interval(mins => s.buffer_before_minutes) as "blocked_start!",
+            a.appointment_end_time + make_interval(mins => s.buffer_after_minutes) as "blocked_end!",
+            COUNT(*) as "booked_seats!"
+        FROM appointments a
+        JOIN services s ON s.id = a.service_id
+        WHERE a.business_id = $1
+        AND a.staff_id IS NOT DISTINCT FROM $4
+        AND a.appointment_end_time + make_interval(mins => s.buffer_after_minutes) > $2
+        AND a.appointment_start_time - make_interval(mins => s.buffer_before_minutes) < $3
+        AND a.status IN ('pending', 'confirmed')
+        AND a.session_id IS NOT NULL
+        GROUP BY a.session_id, a.service_id, a.appointment_start_time, a.appointment_end_time,
+            s.buffer_before_minutes, s.buffer_after_minutes
+        "#,
+        user_id,
+        utc_window_start,
+        utc_window_end,
+        staff.map(|staff| staff.id)
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| internal_server_error_response(e.to_string()))?
+    .into_iter()
+    .map(|session| GroupSession {
+        service_id: session.service_id,
+        start_time: session.appointment_start_time,
+        end_time: session.appointment_end_time,
+        blocked_period: (session.blocked_start, session.blocked_end),
+        booked_seats: session.booked_seats as i32,
+    })
+    .collect();
+
+    let mut db_conn = pool
+        .acquire()
+        .await
+        .map_err(|e| internal_server_error_response(e.to_string()))?;
+
+    // Every seat puts its own event on the calendar, the session itself is
+    // already accounted for, like the bookings of other calendar-less staff
+    let mut known_events: Vec<(OffsetDateTime, OffsetDateTime)> = sessions
+        .iter()
+        .map(|session| (session.start_time, session.end_time))
+        .collect();
+
+    known_events.extend(
+        shared_calendar_bookings(&mut db_conn, staff, (utc_window_start, utc_window_end))
+            .await
+            .map_err(|e| internal_server_error_response(e.to_string()))?,
+    );
+
+    // Google being unreachable should not hide every slot
+    if let Some(access_token) = access_token {
+        if let Ok(busy_periods) = fetch_busy_periods(
+            http_client,
+            access_token,
+            busy_calendar(staff),
+            utc_window_start,
+            utc_window_end,
+        )
+        .await
+        {
+            for busy_period in busy_periods {
+                blocked_periods.extend(subtract_periods(busy_period, &known_events));
+            }
+        }
+    }
+
+    Ok((blocked_periods, sessions))
+}
+
+// One token for every lane of a listing, Google counts each refresh. Without
+// one the listing goes on from bookings alone.
+async fn slot_access_token(
+    pool: &PgPool,
+    config: web::Data<Config>,
+    http_client: &reqwest::Client,
+    user_id: Uuid,
+) -> Option<String> {
+    let auth_record = sqlx::query_as!(Auth, "SELECT * FROM auth WHERE user_id = $1", user_id)
+        .fetch_optional(pool)
+        .await
+        .unwrap_or(None);
+
+    let refresh_token = auth_record?.refresh_token?;
+
+    get_new_access_token(config, http_client, refresh_token)
+        .await
+        .ok()
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+pub fn user_config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/users")
+            .route("/me", web::get().to(get_me))
+            .route("/me/upload-url", web::get().to(get_user_upload_url))
+            .route("/me/status", web::patch().to(set_account_status))
+            .route("/me/availability", web::post().to(set_user_availability))
+            .route("/me/breaks", web::get().to(get_breaks))
+            .route("/me/breaks", web::post().to(set_breaks))
+            .route(
+                "/me/availability/overrides",
+                web::get().to(get_availability_overrides),
+            )
+            .route(
+                "/me/availability/overrides",
+                web::post().to(create_availability_override),
+            )
+            .route(
+                "/me/availability/overrides/{date}",
+                web::put().to(update_availability_override),
+            )
+            .route(
+                "/me/availability/overrides/{date}",
+                web::delete().to(delete_availability_override),
+            )
+            .route("/with-services", web::get().to(get_all_users_with_services))
+            .route("/{id}/slots", web::get().to(get_available_slots))
+            .route(
+                "/{id}/slots/range",
+                web::get().to(get_available_slots_range),
+            )
+            .route("/{id}/slots/next", web::get().to(get_next_available_slots))
             .route(
                 "/{id}/appointments",
                 web::get().to(get_appointments_for_user),
@@ -1,4 +1,11 @@
-use actix_web::{HttpResponse, Responder, get, web};
+use actix_web::{HttpResponse, Responder, ResponseError, get, http::StatusCode, web};
+use chrono_tz::Tz;
+use sqlx::PgConnection;
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+use uuid::Uuid;
 
 use crate::structs::response_struct::ApiResponse;
 #[get("/")]
@@ -42,6 +49,14 @@ pub fn conflict_reponse(message: String) -> HttpResponse {
     })
 }
 
+pub fn unprocessable_entity_response(message: String) -> HttpResponse {
+    HttpResponse::UnprocessableEntity().json(ApiResponse::<()> {
+        success: false,
+        data: None,
+        message: Some(message),
+    })
+}
+
 pub fn internal_server_error_response(message: String) -> HttpResponse {
     eprintln!("Internal Server Error: {}", message);
 
@@ -61,3 +76,130 @@ pub fn expectation_failed_response(message: String) -> HttpResponse {
         success: false,
     })
 }
+
+// Why a booking check failed. Handlers turn it into the matching response,
+// reports gathering several failures keep just the message.
+#[derive(Debug)]
+pub struct BookingError {
+    pub status_code: StatusCode,
+    pub message: String,
+}
+
+impl BookingError {
+    pub fn bad_request(message: String) -> Self {
+        BookingError {
+            status_code: StatusCode::BAD_REQUEST,
+            message,
+        }
+    }
+
+    pub fn conflict(message: String) -> Self {
+        BookingError {
+            status_code: StatusCode::CONFLICT,
+            message,
+        }
+    }
+
+    // Logged here like `internal_server_error_response`, the details stay out
+    // of the message
+    pub fn internal(message: String) -> Self {
+        eprintln!("Internal Server Error: {}", message);
+
+        BookingError {
+            status_code: StatusCode::INTERNAL_SERVER_ERROR,
+            message: "Something went wrong on our end".to_string(),
+        }
+    }
+}
+
+impl Display for BookingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl ResponseError for BookingError {
+    fn status_code(&self) -> StatusCode {
+        self.status_code
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code).json(ApiResponse::<()> {
+            success: false,
+            data: None,
+            message: Some(self.message.clone()),
+        })
+    }
+}
+
+pub fn validate_booking_window(
+    min_notice_minutes: Option<i32>,
+    max_advance_days: Option<i32>,
+) -> Result<(), HttpResponse> {
+    if min_notice_minutes.is_some_and(|m| m < 0) {
+        return Err(bad_request_response(
+            "Minimum notice cannot be negative.".to_string(),
+        ));
+    }
+
+    if max_advance_days.is_some_and(|d| d < 1) {
+        return Err(bad_request_response(
+            "Maximum advance booking must be at least 1 day.".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn validate_service_capacity(capacity: Option<i32>) -> Result<(), HttpResponse> {
+    if capacity.is_some_and(|c| c < 1) {
+        return Err(bad_request_response(
+            "Service capacity must be at least 1.".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn validate_time_zone(time_zone: &str) -> Result<(), HttpResponse> {
+    if Tz::from_str(time_zone).is_err() {
+        return Err(bad_request_response(format!(
+            "Unknown time zone {}. Use an IANA name like Europe/London.",
+            time_zone
+        )));
+    }
+
+    Ok(())
+}
+
+// Staff and resources may only be linked to services of their own business.
+// Hands back the ids sorted and without repeats.
+pub async fn validate_business_services(
+    conn: &mut PgConnection,
+    business_id: Uuid,
+    service_ids: &[Uuid],
+) -> Result<Vec<Uuid>, HttpResponse> {
+    let mut service_ids = service_ids.to_vec();
+    service_ids.sort();
+    service_ids.dedup();
+
+    let owned_count = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) as "count!" FROM services
+        WHERE id = ANY($1) AND user_id = $2
+        "#,
+        &service_ids,
+        business_id
+    )
+    .fetch_one(conn)
+    .await
+    .map_err(|e| internal_server_error_response(e.to_string()))?;
+
+    if owned_count as usize != service_ids.len() {
+        return Err(bad_request_response(
+            "Only services of your own business can be linked.".to_string(),
+        ));
+    }
+
+    Ok(service_ids)
+}
@@ -0,0 +1,252 @@
+use crate::{
+    middlewares::auth_middleware::AuthenticatedUser,
+    routes::utils_routes::{
+        bad_request_response, internal_server_error_response, not_found_response,
+    },
+    structs::{
+        db_struct::{CreateWaitlistEntry, WaitlistEntry},
+        response_struct::ApiResponse,
+        util_struct::WaitlistQuery,
+    },
+};
+use actix_web::{HttpResponse, Responder, web};
+use sqlx::PgPool;
+use time::{Date, macros::format_description};
+use uuid::Uuid;
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+#[utoipa::path(
+    post,
+    path = "/waitlist",
+    tag = "Waitlist",
+    request_body = CreateWaitlistEntry,
+    responses(
+        (status = 201, body = ApiResponse<WaitlistEntry>),
+        (status = 400, description = "Bad Request"),
+        (status = 500, description = "Internal Server Error")
+    )
+)]
+pub async fn create_waitlist_entry(
+    pool: web::Data<PgPool>,
+    body: web::Json<CreateWaitlistEntry>,
+) -> impl Responder {
+    let new_entry = body.into_inner();
+    let customer_name = new_entry.customer_name.trim().to_string();
+    let customer_email = trimmed(new_entry.customer_email);
+    let customer_phone = trimmed(new_entry.customer_phone);
+
+    if customer_name.is_empty() {
+        return bad_request_response("Customer name cannot be empty.".to_string());
+    }
+
+    if customer_email.is_none() && customer_phone.is_none() {
+        return bad_request_response(
+            "An email or a phone number is needed to notify you.".to_string(),
+        );
+    }
+
+    let date_format = format_description!("[year]-[month]-[day]");
+
+    let (preferred_from, preferred_to) = match (
+        Date::parse(&new_entry.preferred_from, date_format),
+        Date::parse(&new_entry.preferred_to, date_format),
+    ) {
+        (Ok(from), Ok(to)) => (from, to),
+        _ => return bad_request_response("Invalid date format (YYYY-MM-DD)".to_string()),
+    };
+
+    if preferred_to < preferred_from {
+        return bad_request_response(
+            "`preferred_to` cannot be before `preferred_from`.".to_string(),
+        );
+    }
+
+    let offers_service = match sqlx::query_scalar!(
+        r#"SELECT EXISTS (SELECT 1 FROM services WHERE id = $1 AND user_id = $2) as "exists!""#,
+        new_entry.service_id,
+        new_entry.business_id
+    )
+    .fetch_one(pool.get_ref())
+    .await
+    {
+        Ok(exists) => exists,
+        Err(e) => return internal_server_error_response(e.to_string()),
+    };
+
+    if !offers_service {
+        return bad_request_response("Invalid service_id.".to_string());
+    }
+
+    match sqlx::query_as!(
+        WaitlistEntry,
+        r#"
+        INSERT INTO waitlist_entries (
+            business_id, service_id, customer_name, customer_email, customer_phone,
+            preferred_from, preferred_to
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING *
+        "#,
+        new_entry.business_id,
+        new_entry.service_id,
+        customer_name,
+        customer_email,
+        customer_phone,
+        preferred_from,
+        preferred_to
+    )
+    .fetch_one(pool.get_ref())
+    .await
+    {
+        Ok(entry) => HttpResponse::Created().json(ApiResponse {
+            success: true,
+            data: Some(entry),
+            message: Some("You are on the waitlist.".to_string()),
+        }),
+
+        Err(e) => internal_server_error_response(e.to_string()),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+#[utoipa::path(
+    get,
+    path = "/waitlist",
+    tag = "Waitlist",
+    params(WaitlistQuery),
+    responses(
+        (status = 200, body = ApiResponse<Vec<WaitlistEntry>>),
+        (status = 500, description = "Internal Server Error")
+    )
+)]
+pub async fn get_waitlist(
+    user: AuthenticatedUser,
+    query: web::Query<WaitlistQuery>,
+    pool: web::Data<PgPool>,
+) -> impl Responder {
+    // In the order the customers will be notified
+    match sqlx::query_as!(
+        WaitlistEntry,
+        r#"
+        SELECT * FROM waitlist_entries
+        WHERE business_id = $1
+        AND ($2::UUID IS NULL OR service_id = $2)
+        ORDER BY created_at, id
+        "#,
+        user.user_id,
+        query.service_id
+    )
+    .fetch_all(pool.get_ref())
+    .await
+    {
+        Ok(entries) => HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(entries),
+            message: None,
+        }),
+
+        Err(e) => internal_server_error_response(e.to_string()),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+#[utoipa::path(
+    delete,
+    path = "/waitlist/{id}",
+    tag = "Waitlist",
+    params(("id" = Uuid, Path, description = "Waitlist entry ID")),
+    responses(
+        (status = 200, description = "Waitlist entry removed"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error")
+    )
+)]
+pub async fn delete_waitlist_entry(
+    path: web::Path<Uuid>,
+    user: AuthenticatedUser,
+    pool: web::Data<PgPool>,
+) -> impl Responder {
+    let entry_id = path.into_inner();
+
+    let business_id = match sqlx::query_scalar!(
+        "SELECT business_id FROM waitlist_entries WHERE id = $1",
+        entry_id
+    )
+    .fetch_one(pool.get_ref())
+    .await
+    {
+        Ok(business_id) => business_id,
+        Err(sqlx::Error::RowNotFound) => {
+            return not_found_response("Waitlist entry not found.".to_string());
+        }
+
+        Err(e) => return internal_server_error_response(e.to_string()),
+    };
+
+    // Check ownership
+    if business_id != user.user_id {
+        return HttpResponse::Forbidden().json(ApiResponse::<()> {
+            success: false,
+            data: None,
+            message: Some("You do not have permission to edit this waitlist.".to_string()),
+        });
+    }
+
+    if let Err(e) = sqlx::query!("DELETE FROM waitlist_entries WHERE id = $1", entry_id)
+        .execute(pool.get_ref())
+        .await
+    {
+        return internal_server_error_response(e.to_string());
+    }
+
+    HttpResponse::Ok().json(ApiResponse::<()> {
+        success: true,
+        data: None,
+        message: Some("Waitlist entry removed successfully.".to_string()),
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+fn trimmed(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+pub fn waitlist_config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/waitlist")
+            .route("", web::post().to(create_waitlist_entry))
+            .route("", web::get().to(get_waitlist))
+            .route("/{id}", web::delete().to(delete_waitlist_entry)),
+    );
+}
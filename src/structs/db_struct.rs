@@ -1,11 +1,13 @@
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::prelude::FromRow;
-use time::{OffsetDateTime, Time};
+use std::{collections::HashMap, str::FromStr};
+use time::{Date, OffsetDateTime, Time};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 time::serde::format_description!(time_format, Time, "[hour]:[minute]:[second]");
+time::serde::format_description!(date_format, Date, "[year]-[month]-[day]");
 
 /* -------------------------------------------------------------------------- */
 /*                                      -                                     */
@@ -84,6 +86,16 @@ pub struct User {
 
     #[serde(with = "time::serde::rfc3339::option")]
     pub last_login: Option<OffsetDateTime>,
+
+    // How far apart offered start times are, services may override it
+    pub slot_interval_minutes: i32,
+
+    // How soon and how far ahead customers may book
+    pub min_notice_minutes: i32,
+    pub max_advance_days: i32,
+
+    // IANA name, every opening hour of the business is read in this zone
+    pub time_zone: String,
 }
 
 #[derive(Deserialize, ToSchema)]
@@ -95,6 +107,10 @@ pub struct UpdateUser {
     pub phone_number: Option<String>,
     pub description: Option<String>,
     pub phone_number_is_whatsapp: Option<bool>,
+    pub slot_interval_minutes: Option<i32>,
+    pub min_notice_minutes: Option<i32>,
+    pub max_advance_days: Option<i32>,
+    pub time_zone: Option<String>,
 }
 
 #[derive(Deserialize, ToSchema)]
@@ -128,6 +144,17 @@ pub struct Service {
 
     #[serde(with = "time::serde::rfc3339::option")]
     pub updated_at: Option<OffsetDateTime>,
+
+    // Prep and cleanup time, blocked but never shown to the customer
+    pub buffer_before_minutes: i32,
+    pub buffer_after_minutes: i32,
+
+    // Each falls back to the business setting when empty
+    pub slot_interval_minutes: Option<i32>,
+    pub min_notice_minutes: Option<i32>,
+    pub max_advance_days: Option<i32>,
+
+    pub capacity: i32, // Seats per slot, above 1 for group classes
 }
 
 #[derive(Deserialize, ToSchema)]
@@ -137,6 +164,12 @@ pub struct CreateService {
     pub price: Option<Decimal>,
     pub duration_minutes: Option<i32>,
     pub category: Option<String>,
+    pub buffer_before_minutes: Option<i32>,
+    pub buffer_after_minutes: Option<i32>,
+    pub slot_interval_minutes: Option<i32>,
+    pub min_notice_minutes: Option<i32>,
+    pub max_advance_days: Option<i32>,
+    pub capacity: Option<i32>, // Defaults to 1
 }
 
 #[derive(Deserialize, ToSchema)]
@@ -146,6 +179,12 @@ pub struct UpdateService {
     pub price: Option<Decimal>,
     pub duration_minutes: Option<i32>,
     pub category: Option<String>,
+    pub buffer_before_minutes: Option<i32>,
+    pub buffer_after_minutes: Option<i32>,
+    pub slot_interval_minutes: Option<i32>,
+    pub min_notice_minutes: Option<i32>,
+    pub max_advance_days: Option<i32>,
+    pub capacity: Option<i32>,
 }
 
 #[derive(Serialize, FromRow, ToSchema)]
@@ -185,9 +224,83 @@ pub struct Appointment {
 
     #[serde(with = "time::serde::rfc3339::option")]
     pub updated_at: Option<OffsetDateTime>,
+
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub cancelled_at: Option<OffsetDateTime>,
+    pub cancellation_reason: Option<String>,
+    pub cancelled_by: Option<String>, // "business" or "customer"
+
+    pub google_event_id: Option<String>,
+    pub google_calendar_id: Option<String>,
+    pub google_event_etag: Option<String>,
+
+    pub status: String, // See AppointmentStatus
+
+    pub staff_id: Option<Uuid>,   // Empty while the business has no staff
+    pub session_id: Option<Uuid>, // Shared by the seats of one group session
+    pub series_id: Option<Uuid>,  // Set on the occurrences of a recurring series
 }
 
-#[derive(Deserialize, ToSchema)]
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AppointmentStatus {
+    Pending,
+    Confirmed,
+    Completed,
+    NoShow,
+    Cancelled,
+}
+
+impl AppointmentStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AppointmentStatus::Pending => "pending",
+            AppointmentStatus::Confirmed => "confirmed",
+            AppointmentStatus::Completed => "completed",
+            AppointmentStatus::NoShow => "no_show",
+            AppointmentStatus::Cancelled => "cancelled",
+        }
+    }
+
+    // Only these still hold their slot
+    pub fn is_active(&self) -> bool {
+        matches!(
+            self,
+            AppointmentStatus::Pending | AppointmentStatus::Confirmed
+        )
+    }
+
+    pub fn can_transition_to(&self, next: AppointmentStatus) -> bool {
+        match self {
+            AppointmentStatus::Pending => next != AppointmentStatus::Pending,
+            AppointmentStatus::Confirmed => matches!(
+                next,
+                AppointmentStatus::Completed
+                    | AppointmentStatus::NoShow
+                    | AppointmentStatus::Cancelled
+            ),
+            // Completed, no-show and cancelled bookings are final
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for AppointmentStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(AppointmentStatus::Pending),
+            "confirmed" => Ok(AppointmentStatus::Confirmed),
+            "completed" => Ok(AppointmentStatus::Completed),
+            "no_show" => Ok(AppointmentStatus::NoShow),
+            "cancelled" => Ok(AppointmentStatus::Cancelled),
+            _ => Err(format!("Unknown appointment status: {}", s)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct CreateAppointment {
     pub service_id: Uuid,
     pub business_id: Uuid,
@@ -198,6 +311,120 @@ pub struct CreateAppointment {
 
     #[serde(with = "time::serde::rfc3339")]
     pub appointment_start_time: OffsetDateTime,
+
+    pub staff_id: Option<Uuid>, // A free staff member is picked when empty
+    pub hold_token: Option<Uuid>, // Redeems a hold, the booking must be for its slot
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CancelAppointment {
+    pub reason: Option<String>,
+    // Required when the customer (not the business) is cancelling
+    pub customer_email: Option<String>,
+
+    #[serde(default)]
+    pub scope: CancelScope,
+}
+
+// How much of a recurring series a cancellation takes with it
+#[derive(Deserialize, Default, Clone, Copy, PartialEq, Eq, Debug, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CancelScope {
+    #[default]
+    This, // Only the given occurrence
+    Following, // The given occurrence and every later one
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct RescheduleAppointment {
+    #[serde(with = "time::serde::rfc3339")]
+    pub appointment_start_time: OffsetDateTime,
+
+    // Required when the customer (not the business) is rescheduling
+    pub customer_email: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RecurrenceFrequency {
+    Weekly,
+    Biweekly,
+}
+
+impl RecurrenceFrequency {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecurrenceFrequency::Weekly => "weekly",
+            RecurrenceFrequency::Biweekly => "biweekly",
+        }
+    }
+
+    pub fn interval_weeks(&self) -> i64 {
+        match self {
+            RecurrenceFrequency::Weekly => 1,
+            RecurrenceFrequency::Biweekly => 2,
+        }
+    }
+}
+
+// Ends after `count` occurrences or on the `until` date, exactly one of them
+#[derive(Deserialize, ToSchema)]
+pub struct RecurrenceRule {
+    pub frequency: RecurrenceFrequency,
+    pub count: Option<i32>,
+
+    #[schema(value_type = Option<String>, format = "date")]
+    pub until: Option<String>, // "YYYY-MM-DD", local date of the business
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateAppointmentSeries {
+    // The first occurrence, the others follow at the same local time
+    #[serde(flatten)]
+    pub appointment: CreateAppointment,
+
+    pub recurrence: RecurrenceRule,
+}
+
+#[derive(Serialize, FromRow, ToSchema)]
+pub struct AppointmentSeries {
+    pub id: Uuid,
+    pub business_id: Uuid,
+    pub service_id: Uuid,
+    pub frequency: String, // See RecurrenceFrequency
+    pub occurrence_count: Option<i32>,
+
+    #[serde(with = "date_format::option")]
+    #[schema(value_type = Option<String>, format = "date")]
+    pub until_date: Option<Date>,
+
+    pub google_event_id: Option<String>,
+    pub google_calendar_id: Option<String>,
+
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub created_at: Option<OffsetDateTime>,
+
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+// An occurrence of a new series that could not be booked, and why
+#[derive(Serialize, ToSchema)]
+pub struct FailedOccurrence {
+    pub local_start_time: String, // "YYYY-MM-DDTHH:MM:SS" in the business time zone
+
+    // Empty when that local time does not exist on the day
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub appointment_start_time: Option<OffsetDateTime>,
+
+    pub reason: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct AppointmentSeriesResult {
+    pub series: AppointmentSeries,
+    pub appointments: Vec<Appointment>,
+    pub failed_occurrences: Vec<FailedOccurrence>,
 }
 
 #[derive(Serialize, ToSchema)]
@@ -207,6 +434,24 @@ pub struct GoogleCalendarEvent {
     pub start: GoogleEventDateTime,
     pub end: GoogleEventDateTime,
     pub attendees: Vec<GoogleEventAttendee>,
+
+    #[serde(rename = "extendedProperties")]
+    pub extended_properties: GoogleEventExtendedProperties,
+
+    // RRULE and EXDATE lines of a recurring event
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub recurrence: Vec<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct GoogleEventTimesPatch {
+    pub start: GoogleEventDateTime,
+    pub end: GoogleEventDateTime,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct GoogleEventRecurrencePatch {
+    pub recurrence: Vec<String>,
 }
 
 #[derive(Serialize, ToSchema)]
@@ -223,6 +468,43 @@ pub struct GoogleEventAttendee {
     pub email: String,
 }
 
+#[derive(Serialize, ToSchema)]
+pub struct GoogleEventExtendedProperties {
+    // Ties the event back to the appointment it was created for
+    pub private: HashMap<String, String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct GoogleEventList {
+    #[serde(default)]
+    pub items: Vec<GoogleEventItem>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct GoogleEventItem {
+    pub id: String,
+    pub etag: Option<String>,
+    pub organizer: Option<GoogleEventOrganizer>,
+    pub start: Option<GoogleEventItemTime>,
+    pub end: Option<GoogleEventItemTime>,
+
+    // RRULE and EXDATE lines, recurring events only
+    #[serde(default)]
+    pub recurrence: Vec<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct GoogleEventItemTime {
+    // All-day events only carry a date
+    #[serde(rename = "dateTime")]
+    pub date_time: Option<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct GoogleEventOrganizer {
+    pub email: Option<String>,
+}
+
 /* -------------------------------------------------------------------------- */
 /*                                      -                                     */
 /* -------------------------------------------------------------------------- */
@@ -238,7 +520,6 @@ pub struct AvailabilityRule {
     pub id: Uuid,
     pub user_id: Uuid,
     pub day_of_week: i32,
-    pub time_zone: String,
 
     #[serde(with = "time_format")]
     #[schema(value_type = String, format = "date-time")]
@@ -259,6 +540,9 @@ pub struct AvailabilityRule {
 pub struct SetAvailability {
     #[serde(rename = "slots")]
     pub rules: Vec<DayTimeSlot>,
+
+    // Updates the business time zone when given
+    pub time_zone: Option<String>,
 }
 
 #[derive(Deserialize, ToSchema)]
@@ -266,5 +550,300 @@ pub struct DayTimeSlot {
     pub day_of_week: i32,
     pub open_time: String,
     pub close_time: String,
-    pub time_zone: String,
+
+    // Older clients send the zone per rule, it must match the others
+    pub time_zone: Option<String>,
+}
+
+// No times means the business is closed for the whole date
+#[derive(Serialize, FromRow, ToSchema)]
+pub struct AvailabilityOverride {
+    pub id: Uuid,
+    pub user_id: Uuid,
+
+    #[serde(with = "date_format")]
+    #[schema(value_type = String, format = "date")]
+    pub override_date: Date,
+
+    #[serde(with = "time_format::option")]
+    #[schema(value_type = Option<String>)]
+    pub open_time: Option<Time>,
+
+    #[serde(with = "time_format::option")]
+    #[schema(value_type = Option<String>)]
+    pub close_time: Option<Time>,
+
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub created_at: Option<OffsetDateTime>,
+
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateAvailabilityOverride {
+    #[schema(value_type = String, format = "date")]
+    pub date: String, // Format should be "YYYY-MM-DD"
+
+    #[serde(flatten)]
+    pub hours: SetAvailabilityOverride,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct SetAvailabilityOverride {
+    #[serde(default)]
+    pub is_closed: bool,
+
+    #[serde(default)]
+    pub windows: Vec<OverrideWindow>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct OverrideWindow {
+    pub open_time: String,
+    pub close_time: String,
+}
+
+// Recurring time off inside the opening hours, like a lunch break
+#[derive(Serialize, FromRow, ToSchema)]
+pub struct BusinessBreak {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub day_of_week: i32,
+
+    #[serde(with = "time_format")]
+    #[schema(value_type = String)]
+    pub start_time: Time,
+
+    #[serde(with = "time_format")]
+    #[schema(value_type = String)]
+    pub end_time: Time,
+
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub created_at: Option<OffsetDateTime>,
+
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct SetBreaks {
+    pub breaks: Vec<BreakWindow>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct BreakWindow {
+    pub day_of_week: i32,
+    pub start_time: String,
+    pub end_time: String,
+}
+
+// Limits a service to part of the business hours, no rows means no limit
+#[derive(Serialize, FromRow, ToSchema)]
+pub struct ServiceAvailabilityRule {
+    pub id: Uuid,
+    pub service_id: Uuid,
+    pub day_of_week: i32,
+
+    #[serde(with = "time_format")]
+    #[schema(value_type = String)]
+    pub open_time: Time,
+
+    #[serde(with = "time_format")]
+    #[schema(value_type = String)]
+    pub close_time: Time,
+
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub created_at: Option<OffsetDateTime>,
+
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+// An empty list lifts the limit again
+#[derive(Deserialize, ToSchema)]
+pub struct SetServiceAvailability {
+    #[serde(rename = "slots")]
+    pub rules: Vec<DayTimeSlot>,
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                    STAFF                                   */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+#[derive(Serialize, FromRow, ToSchema, Clone)]
+pub struct Staff {
+    pub id: Uuid,
+    pub business_id: Uuid,
+    pub name: String,
+    pub email: Option<String>,
+
+    // Calendar shared with the business Google account, checked and written
+    // instead of the primary one when set
+    pub google_calendar_id: Option<String>,
+    pub is_active: bool,
+
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub created_at: Option<OffsetDateTime>,
+
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct StaffWithServices {
+    pub staff: Staff,
+    pub service_ids: Vec<Uuid>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateStaff {
+    pub name: String,
+    pub email: Option<String>,
+    pub google_calendar_id: Option<String>,
+
+    #[serde(default)]
+    pub service_ids: Vec<Uuid>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateStaff {
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub google_calendar_id: Option<String>, // Empty clears it, back to the primary calendar
+    pub is_active: Option<bool>,
+    pub service_ids: Option<Vec<Uuid>>, // Replaces the linked services when given
+}
+
+// Working hours inside the business hours, no rows means all of them
+#[derive(Serialize, FromRow, ToSchema)]
+pub struct StaffAvailabilityRule {
+    pub id: Uuid,
+    pub staff_id: Uuid,
+    pub day_of_week: i32,
+
+    #[serde(with = "time_format")]
+    #[schema(value_type = String)]
+    pub open_time: Time,
+
+    #[serde(with = "time_format")]
+    #[schema(value_type = String)]
+    pub close_time: Time,
+
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub created_at: Option<OffsetDateTime>,
+
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct SetStaffAvailability {
+    #[serde(rename = "slots")]
+    pub rules: Vec<DayTimeSlot>,
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                  RESOURCES                                 */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+// A room, chair or machine that bookings of its services reserve
+#[derive(Serialize, FromRow, ToSchema)]
+pub struct Resource {
+    pub id: Uuid,
+    pub business_id: Uuid,
+    pub name: String,
+    pub capacity: i32, // How many bookings can hold it at the same time
+
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub created_at: Option<OffsetDateTime>,
+
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ResourceWithServices {
+    pub resource: Resource,
+    pub service_ids: Vec<Uuid>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateResource {
+    pub name: String,
+    pub capacity: Option<i32>, // Defaults to 1
+
+    #[serde(default)]
+    pub service_ids: Vec<Uuid>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateResource {
+    pub name: Option<String>,
+    pub capacity: Option<i32>,
+    pub service_ids: Option<Vec<Uuid>>, // Replaces the linked services when given
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                  WAITLIST                                  */
+/* -------------------------------------------------------------------------- */
+/* -------------------------------------------------------------------------- */
+/*                                      -                                     */
+/* -------------------------------------------------------------------------- */
+
+// A customer waiting for an opening of a service within a date range
+#[derive(Serialize, FromRow, Clone, ToSchema)]
+pub struct WaitlistEntry {
+    pub id: Uuid,
+    pub business_id: Uuid,
+    pub service_id: Uuid,
+    pub customer_name: String,
+    pub customer_email: Option<String>,
+    pub customer_phone: Option<String>,
+
+    #[serde(with = "date_format")]
+    #[schema(value_type = String, format = "date")]
+    pub preferred_from: Date,
+
+    #[serde(with = "date_format")]
+    #[schema(value_type = String, format = "date")]
+    pub preferred_to: Date,
+
+    // When an opening was last passed on. The entry stays in line until it is
+    // removed, the customer may not have got the slot.
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub notified_at: Option<OffsetDateTime>,
+
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub created_at: Option<OffsetDateTime>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateWaitlistEntry {
+    pub business_id: Uuid,
+    pub service_id: Uuid,
+    pub customer_name: String,
+    pub customer_email: Option<String>, // An email or a phone number is needed
+    pub customer_phone: Option<String>,
+
+    #[schema(value_type = String, format = "date")]
+    pub preferred_from: String, // Format should be "YYYY-MM-DD"
+
+    #[schema(value_type = String, format = "date")]
+    pub preferred_to: String, // Inclusive
 }
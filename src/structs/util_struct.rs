@@ -1,7 +1,9 @@
+use crate::structs::db_struct::{AppointmentStatus, AvailabilityOverride, AvailabilityRule, Staff};
 use serde::Deserialize;
 use serde::{self, Serialize};
 use std::collections::HashMap;
-use utoipa::ToSchema;
+use time::{Date, OffsetDateTime, Time};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 #[derive(Deserialize, ToSchema)]
@@ -21,12 +23,111 @@ pub struct SlotQuery {
     #[schema(value_type = String, format = "date")]
     pub date: String, // Format should be "YYYY-MM-DD"
     pub service_id: Uuid,
+    pub staff_id: Option<Uuid>, // Any staff member when empty
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct AppointmentQuery {
+    pub status: Option<AppointmentStatus>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct StaffQuery {
+    pub business_id: Uuid,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct ResourceQuery {
+    pub business_id: Uuid,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct SlotRangeQuery {
+    #[param(value_type = String, format = "date")]
+    pub from: String, // Format should be "YYYY-MM-DD", inclusive
+
+    #[param(value_type = String, format = "date")]
+    pub to: String, // Inclusive as well
+    pub service_id: Uuid,
+    pub staff_id: Option<Uuid>, // Any staff member when empty
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct NextSlotQuery {
+    pub service_id: Uuid,
+    pub staff_id: Option<Uuid>, // Any staff member when empty
+    pub limit: Option<usize>,   // How many openings to return, defaults to 1
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DaySlots {
+    pub date: String, // Local date of the business, "YYYY-MM-DD"
+    pub slots: Vec<TimeSlot>,
+}
+
+// Points at the offending entry of a SetAvailability payload
+#[derive(Serialize, ToSchema, Debug, PartialEq)]
+pub struct AvailabilityRuleError {
+    pub index: usize, // Position in the submitted `slots`, from 0
+    pub field: String,
+    pub message: String,
 }
 
 #[derive(Serialize, Deserialize, ToSchema)]
 pub struct TimeSlot {
     pub start_time: String, // ISO 8601 / RFC 3339
     pub end_time: String,
+
+    // Lists cached before group classes existed only held one-seat slots
+    #[serde(default = "one_seat")]
+    pub remaining_seats: i32,
+}
+
+fn one_seat() -> i32 {
+    1
+}
+
+// One calendar that can take bookings, a staff member or the whole business
+pub struct BookingLane {
+    pub staff: Option<Staff>,
+    pub blocked_periods: Vec<(OffsetDateTime, OffsetDateTime)>,
+    pub reserved_periods: Vec<(OffsetDateTime, OffsetDateTime)>,
+    pub sessions: Vec<GroupSession>,
+}
+
+// What shapes the opening hours over a date range, loaded in one go so the
+// number of queries does not grow with the days searched
+pub struct OpeningSchedule {
+    pub overrides: HashMap<Date, Vec<AvailabilityOverride>>,
+    pub weekly_rules: HashMap<i32, Vec<AvailabilityRule>>,
+    pub service_rules: Vec<(i32, Time, Time)>,
+    pub staff_rules: HashMap<Uuid, Vec<(i32, Time, Time)>>,
+}
+
+// Unavailable time that holds for every lane of a search
+pub struct SharedBlockedPeriods {
+    pub breaks: Vec<(OffsetDateTime, OffsetDateTime)>,
+    pub reserved: Vec<(OffsetDateTime, OffsetDateTime)>, // Resources at capacity
+    pub holds: Vec<SlotHold>,                            // Each lane keeps its own
+}
+
+// A group class of the lane with seats already taken
+#[derive(Clone)]
+pub struct GroupSession {
+    pub service_id: Uuid,
+    pub start_time: OffsetDateTime,
+    pub end_time: OffsetDateTime,
+    pub blocked_period: (OffsetDateTime, OffsetDateTime), // Buffers included
+    pub booked_seats: i32,
+}
+
+// Everything about a service that shapes its slots, in minutes
+pub struct SlotSettings {
+    pub duration: i64,
+    pub interval: i64,
+    pub buffer_before: i64,
+    pub buffer_after: i64,
+    pub capacity: i32, // Seats per slot
 }
 
 #[derive(Serialize, ToSchema)]
@@ -58,3 +159,71 @@ pub struct FreeBusyTime {
     pub start: String, // RFC3339 string
     pub end: String,   // RFC3339 string
 }
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateSlotHold {
+    pub business_id: Uuid,
+    pub service_id: Uuid,
+
+    #[serde(with = "time::serde::rfc3339")]
+    pub appointment_start_time: OffsetDateTime,
+
+    pub staff_id: Option<Uuid>, // A free staff member is picked when empty
+}
+
+// A slot set aside for one customer while they check out, kept in Redis until
+// it expires or is redeemed by a booking
+#[derive(Serialize, Deserialize, Clone, ToSchema)]
+pub struct SlotHold {
+    pub hold_token: Uuid,
+    pub business_id: Uuid,
+    pub service_id: Uuid,
+    pub staff_id: Option<Uuid>,
+
+    #[serde(with = "time::serde::rfc3339")]
+    pub start_time: OffsetDateTime,
+
+    #[serde(with = "time::serde::rfc3339")]
+    pub end_time: OffsetDateTime,
+
+    // The window the hold keeps free, prep and cleanup included
+    #[serde(with = "time::serde::rfc3339")]
+    pub blocked_start: OffsetDateTime,
+
+    #[serde(with = "time::serde::rfc3339")]
+    pub blocked_end: OffsetDateTime,
+
+    #[serde(with = "time::serde::rfc3339")]
+    pub expires_at: OffsetDateTime,
+
+    // Who asked for it, a client gets one live hold at a time
+    #[serde(default)]
+    pub client_ip: Option<String>,
+}
+
+// What a waitlisted customer is told when a slot of their service opens up
+#[derive(Serialize)]
+pub struct WaitlistNotification {
+    pub waitlist_entry_id: Uuid,
+    pub business_id: Uuid,
+    pub service_id: Uuid,
+    pub service_name: String,
+    pub customer_name: String,
+    pub customer_email: Option<String>,
+    pub customer_phone: Option<String>,
+
+    #[serde(with = "time::serde::rfc3339")]
+    pub slot_start_time: OffsetDateTime,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct WaitlistQuery {
+    pub service_id: Option<Uuid>, // Every service when empty
+}
+
+// What an Idempotency-Key of a booking request stands for
+#[derive(Serialize, Deserialize)]
+pub struct IdempotencyRecord {
+    pub payload: serde_json::Value,
+    pub response: Option<serde_json::Value>, // Still being processed when empty
+}
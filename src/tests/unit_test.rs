@@ -1,8 +1,38 @@
 use crate::{
-    structs::util_struct::TimeSlot,
-    utils::others_utils::{convert_to_local_primitive, generate_slots},
+    structs::{
+        db_struct::{
+            AppointmentStatus, AvailabilityOverride, AvailabilityRule, BusinessBreak, DayTimeSlot,
+            GoogleEventItem, GoogleEventItemTime, WaitlistEntry,
+        },
+        util_struct::{
+            GroupSession, IdempotencyRecord, OpeningSchedule, SlotHold, SlotSettings, TimeSlot,
+        },
+    },
+    utils::{
+        calendar_utils::event_spans,
+        idempotency_utils::{IdempotencyOutcome, idempotency_outcome},
+        others_utils::{
+            AmbiguousTime, apply_group_sessions, booking_window, break_periods, check_hold_limit,
+            client_ip, convert_to_local_primitive, cross_lane_hold_periods,
+            effective_opening_hours, end_recurrence_before, fits_opening_hours, generate_day_slots,
+            generate_slots, held_seats, hold_sessions, intersect_opening_hours, lanes_collide,
+            local_dates, merge_lane_slots, order_lane_candidates, override_windows_overlap,
+            recurring_instance_id, resolve_local_time, retain_bookable_slots, saturated_periods,
+            schedule_hours, series_local_starts, series_recurrence, subtract_period,
+            validate_weekly_rules, waitlist_openings, waitlist_recipients,
+        },
+    },
 };
-use time::{Duration, macros::datetime};
+use std::{
+    collections::{HashMap, HashSet},
+    net::IpAddr,
+    str::FromStr,
+};
+use time::{
+    Duration,
+    macros::{date, datetime, time},
+};
+use uuid::Uuid;
 
 #[test]
 fn test_timezone_conversion_works() {
@@ -20,6 +50,20 @@ fn test_timezone_conversion_works() {
     assert_eq!(local_time.hour(), 11);
 }
 
+#[test]
+fn test_local_dates_follow_the_business_time_zone() {
+    // 03:30 UTC is still the previous evening in New York
+    let dates = local_dates(
+        &[
+            datetime!(2025-03-04 03:30:00 UTC),
+            datetime!(2025-03-03 15:00:00 UTC),
+        ],
+        "America/New_York",
+    );
+
+    assert_eq!(dates, vec![date!(2025 - 03 - 03)]);
+}
+
 #[test]
 fn test_invalid_timezone_fails() {
     let utc_time = datetime!(2025-12-25 10:00:00 UTC);
@@ -77,6 +121,7 @@ fn test_timeslot_serializes_correctly() {
     let slot = TimeSlot {
         start_time: "2025-12-25T09:00:00+00:00".to_string(),
         end_time: "2025-12-25T10:00:00+00:00".to_string(),
+        remaining_seats: 1,
     };
 
     let json_output = serde_json::to_value(&slot).expect("Failed to serialize");
@@ -84,6 +129,7 @@ fn test_timeslot_serializes_correctly() {
     // Assert keys match exactly what Frontend expects
     assert_eq!(json_output["start_time"], "2025-12-25T09:00:00+00:00");
     assert_eq!(json_output["end_time"], "2025-12-25T10:00:00+00:00");
+    assert_eq!(json_output["remaining_seats"], 1);
 }
 
 /* -------------------------------------------------------------------------- */
@@ -102,7 +148,14 @@ fn test_slot_generation_blocks_overlaps() {
     )];
 
     // Generate 60 min slots
-    let slots = generate_slots(start, end, 60, &chrono_tz::Tz::UTC, &blocked);
+    let settings = SlotSettings {
+        duration: 60,
+        interval: 30,
+        buffer_before: 0,
+        buffer_after: 0,
+        capacity: 1,
+    };
+    let slots = generate_slots(start, end, &settings, &chrono_tz::Tz::UTC, &blocked);
 
     // Assert
     // Should have: 09:00-10:00 (OK), 10:00-11:00 (BLOCKED), 11:00-12:00 (OK)
@@ -110,3 +163,1328 @@ fn test_slot_generation_blocks_overlaps() {
     assert_eq!(slots[0].start_time, "2025-01-01T09:00:00Z");
     assert_eq!(slots[1].start_time, "2025-01-01T11:00:00Z");
 }
+
+#[test]
+fn test_slot_generation_respects_buffers() {
+    // Setup: 9:00 to 12:00 window
+    let start = datetime!(2025-01-01 09:00:00);
+    let end = datetime!(2025-01-01 12:00:00);
+
+    // Block: 10:00 to 10:30 is BUSY
+    let blocked = vec![(
+        datetime!(2025-01-01 10:00:00 UTC),
+        datetime!(2025-01-01 10:30:00 UTC),
+    )];
+
+    // Generate 30 min slots needing 15 min prep and 30 min cleanup
+    let settings = SlotSettings {
+        duration: 30,
+        interval: 30,
+        buffer_before: 15,
+        buffer_after: 30,
+        capacity: 1,
+    };
+    let slots = generate_slots(start, end, &settings, &chrono_tz::Tz::UTC, &blocked);
+
+    // 09:30 would clean up into the busy block, 10:30 would prep inside it
+    let starts: Vec<&str> = slots.iter().map(|s| s.start_time.as_str()).collect();
+    assert_eq!(
+        starts,
+        vec![
+            "2025-01-01T09:00:00Z",
+            "2025-01-01T11:00:00Z",
+            "2025-01-01T11:30:00Z"
+        ]
+    );
+
+    // Buffers never change the times shown to the customer
+    assert_eq!(slots[0].end_time, "2025-01-01T09:30:00Z");
+}
+
+#[test]
+fn test_slot_generation_uses_configured_interval() {
+    // Setup: 9:00 to 10:30 window, nothing blocked
+    let start = datetime!(2025-01-01 09:00:00);
+    let end = datetime!(2025-01-01 10:30:00);
+
+    // 15 min steps offer quarter-hour starts
+    let settings = SlotSettings {
+        duration: 30,
+        interval: 15,
+        buffer_before: 0,
+        buffer_after: 0,
+        capacity: 1,
+    };
+    let slots = generate_slots(start, end, &settings, &chrono_tz::Tz::UTC, &[]);
+
+    let starts: Vec<&str> = slots.iter().map(|s| s.start_time.as_str()).collect();
+    assert_eq!(
+        starts,
+        vec![
+            "2025-01-01T09:00:00Z",
+            "2025-01-01T09:15:00Z",
+            "2025-01-01T09:30:00Z",
+            "2025-01-01T09:45:00Z",
+            "2025-01-01T10:00:00Z"
+        ]
+    );
+
+    // A 45 min service stepping by its own length packs back to back
+    let settings = SlotSettings {
+        duration: 45,
+        interval: 45,
+        buffer_before: 0,
+        buffer_after: 0,
+        capacity: 1,
+    };
+    let slots = generate_slots(start, end, &settings, &chrono_tz::Tz::UTC, &[]);
+
+    assert_eq!(slots.len(), 2);
+    assert_eq!(slots[0].end_time, "2025-01-01T09:45:00Z");
+    assert_eq!(slots[1].start_time, "2025-01-01T09:45:00Z");
+    assert_eq!(slots[1].end_time, "2025-01-01T10:30:00Z");
+}
+
+#[test]
+fn test_subtract_period_keeps_surrounding_busy_time() {
+    let busy = (
+        datetime!(2025-01-01 09:00:00 UTC),
+        datetime!(2025-01-01 12:00:00 UTC),
+    );
+
+    // Cutting the middle leaves both ends
+    let pieces = subtract_period(
+        busy,
+        (
+            datetime!(2025-01-01 10:00:00 UTC),
+            datetime!(2025-01-01 11:00:00 UTC),
+        ),
+    );
+    assert_eq!(
+        pieces,
+        vec![
+            (
+                datetime!(2025-01-01 09:00:00 UTC),
+                datetime!(2025-01-01 10:00:00 UTC)
+            ),
+            (
+                datetime!(2025-01-01 11:00:00 UTC),
+                datetime!(2025-01-01 12:00:00 UTC)
+            ),
+        ]
+    );
+
+    // Cutting everything leaves nothing, cutting elsewhere leaves it intact
+    assert!(subtract_period(busy, busy).is_empty());
+    assert_eq!(
+        subtract_period(
+            busy,
+            (
+                datetime!(2025-01-01 13:00:00 UTC),
+                datetime!(2025-01-01 14:00:00 UTC)
+            )
+        ),
+        vec![busy]
+    );
+}
+
+#[test]
+fn test_event_spans_matches_the_booked_times_only() {
+    let event = GoogleEventItem {
+        id: "evt_1".to_string(),
+        etag: Some("\"2\"".to_string()),
+        organizer: None,
+        start: Some(GoogleEventItemTime {
+            date_time: Some("2025-01-01T10:00:00+01:00".to_string()),
+        }),
+        end: Some(GoogleEventItemTime {
+            date_time: Some("2025-01-01T11:00:00+01:00".to_string()),
+        }),
+        recurrence: Vec::new(),
+    };
+
+    // Same instants, written in another offset
+    assert!(event_spans(
+        &event,
+        datetime!(2025-01-01 09:00:00 UTC),
+        datetime!(2025-01-01 10:00:00 UTC)
+    ));
+
+    // Moved in Google Calendar
+    assert!(!event_spans(
+        &event,
+        datetime!(2025-01-01 10:00:00 UTC),
+        datetime!(2025-01-01 11:00:00 UTC)
+    ));
+}
+
+#[test]
+fn test_booking_window_hides_too_soon_and_too_far_slots() {
+    let now = datetime!(2025-01-01 09:10:00 UTC);
+
+    // 2 hours notice, 30 days ahead
+    let (earliest, latest) = booking_window(now, 120, 30);
+    assert_eq!(earliest, datetime!(2025-01-01 11:10:00 UTC));
+    assert_eq!(latest, datetime!(2025-01-31 09:10:00 UTC));
+
+    let slot = |start: &str| TimeSlot {
+        start_time: start.to_string(),
+        end_time: start.to_string(),
+        remaining_seats: 1,
+    };
+
+    let mut slots = vec![
+        slot("2025-01-01T09:00:00Z"), // Already started
+        slot("2025-01-01T11:00:00Z"), // Inside the notice period
+        slot("2025-01-01T11:30:00Z"),
+        slot("2025-01-31T09:00:00Z"),
+        slot("2025-01-31T09:30:00Z"), // Past the advance limit
+    ];
+    retain_bookable_slots(&mut slots, earliest, latest);
+
+    let starts: Vec<&str> = slots.iter().map(|s| s.start_time.as_str()).collect();
+    assert_eq!(starts, vec!["2025-01-01T11:30:00Z", "2025-01-31T09:00:00Z"]);
+}
+
+/* -------------------------------------------------------------------------- */
+/*                     DATE OVERRIDES (Precedence Check)                      */
+/* -------------------------------------------------------------------------- */
+#[test]
+fn test_date_overrides_take_precedence_over_weekly_rules() {
+    let weekly = vec![AvailabilityRule {
+        id: Uuid::new_v4(),
+        user_id: Uuid::nil(),
+        day_of_week: 3,
+        open_time: time!(09:00),
+        close_time: time!(17:00),
+        created_at: None,
+        updated_at: None,
+    }];
+
+    // No override, the weekly rule applies
+    assert_eq!(
+        effective_opening_hours(&[], &weekly),
+        vec![(time!(09:00), time!(17:00))]
+    );
+
+    // A holiday closes the whole day
+    let closed = vec![AvailabilityOverride {
+        id: Uuid::new_v4(),
+        user_id: Uuid::nil(),
+        override_date: date!(2025 - 01 - 01),
+        open_time: None,
+        close_time: None,
+        created_at: None,
+        updated_at: None,
+    }];
+    assert!(effective_opening_hours(&closed, &weekly).is_empty());
+
+    // Custom hours replace the weekly ones entirely
+    let custom = vec![
+        AvailabilityOverride {
+            id: Uuid::new_v4(),
+            user_id: Uuid::nil(),
+            override_date: date!(2025 - 01 - 01),
+            open_time: Some(time!(10:00)),
+            close_time: Some(time!(12:00)),
+            created_at: None,
+            updated_at: None,
+        },
+        AvailabilityOverride {
+            id: Uuid::new_v4(),
+            user_id: Uuid::nil(),
+            override_date: date!(2025 - 01 - 01),
+            open_time: Some(time!(14:00)),
+            close_time: Some(time!(16:00)),
+            created_at: None,
+            updated_at: None,
+        },
+    ];
+    assert_eq!(
+        effective_opening_hours(&custom, &weekly),
+        vec![(time!(10:00), time!(12:00)), (time!(14:00), time!(16:00))]
+    );
+
+    // A special opening on a day with no weekly rules
+    let special = vec![AvailabilityOverride {
+        id: Uuid::new_v4(),
+        user_id: Uuid::nil(),
+        override_date: date!(2025 - 01 - 01),
+        open_time: Some(time!(11:00)),
+        close_time: Some(time!(15:00)),
+        created_at: None,
+        updated_at: None,
+    }];
+    assert_eq!(
+        effective_opening_hours(&special, &[]),
+        vec![(time!(11:00), time!(15:00))]
+    );
+}
+
+#[test]
+fn test_override_windows_overlap_past_midnight() {
+    // Back to back is fine
+    assert!(!override_windows_overlap(&[
+        (time!(09:00), time!(12:00)),
+        (time!(12:00), time!(15:00)),
+    ]));
+
+    assert!(override_windows_overlap(&[
+        (time!(14:00), time!(16:00)),
+        (time!(09:00), time!(14:30)),
+    ]));
+
+    // A window running past midnight covers the evening of the date
+    assert!(override_windows_overlap(&[
+        (time!(22:00), time!(02:00)),
+        (time!(23:00), time!(23:30)),
+    ]));
+
+    // Its early hours belong to the next date
+    assert!(!override_windows_overlap(&[
+        (time!(22:00), time!(02:00)),
+        (time!(01:00), time!(03:00)),
+    ]));
+}
+
+/* -------------------------------------------------------------------------- */
+/*                    OVERNIGHT HOURS (Midnight Crossing)                     */
+/* -------------------------------------------------------------------------- */
+#[test]
+fn test_overnight_hours_split_slots_across_both_days() {
+    // 23:00 to 02:00, hourly slots
+    let overnight = vec![(time!(23:00), time!(02:00))];
+    let settings = SlotSettings {
+        duration: 60,
+        interval: 60,
+        buffer_before: 0,
+        buffer_after: 0,
+        capacity: 1,
+    };
+
+    // The starting day only keeps the slot that starts before midnight
+    let friday = generate_day_slots(
+        date!(2025 - 01 - 03),
+        &overnight,
+        &[],
+        &settings,
+        &chrono_tz::Tz::UTC,
+        &[],
+    );
+    assert_eq!(friday.len(), 1);
+    assert_eq!(friday[0].start_time, "2025-01-03T23:00:00Z");
+    assert_eq!(friday[0].end_time, "2025-01-04T00:00:00Z");
+
+    // The spill-over day gets the rest of the shift, even with no hours of its own
+    let saturday = generate_day_slots(
+        date!(2025 - 01 - 04),
+        &[],
+        &overnight,
+        &settings,
+        &chrono_tz::Tz::UTC,
+        &[],
+    );
+    let starts: Vec<&str> = saturday.iter().map(|s| s.start_time.as_str()).collect();
+    assert_eq!(starts, vec!["2025-01-04T00:00:00Z", "2025-01-04T01:00:00Z"]);
+}
+
+#[test]
+fn test_bookings_may_cross_midnight_inside_overnight_hours() {
+    let overnight = vec![(time!(23:00), time!(04:00))];
+
+    // Starts before midnight, ends after it
+    assert!(fits_opening_hours(
+        datetime!(2025-01-03 23:30:00 UTC),
+        datetime!(2025-01-04 00:30:00 UTC),
+        date!(2025 - 01 - 03),
+        &overnight,
+        &[],
+        &chrono_tz::Tz::UTC,
+    ));
+
+    // Starts after midnight, inside the previous day's shift
+    assert!(fits_opening_hours(
+        datetime!(2025-01-04 03:00:00 UTC),
+        datetime!(2025-01-04 04:00:00 UTC),
+        date!(2025 - 01 - 04),
+        &[],
+        &overnight,
+        &chrono_tz::Tz::UTC,
+    ));
+
+    // Runs past the end of the shift
+    assert!(!fits_opening_hours(
+        datetime!(2025-01-04 03:30:00 UTC),
+        datetime!(2025-01-04 04:30:00 UTC),
+        date!(2025 - 01 - 04),
+        &[],
+        &overnight,
+        &chrono_tz::Tz::UTC,
+    ));
+
+    // Regular daytime hours still cannot cross midnight
+    assert!(!fits_opening_hours(
+        datetime!(2025-01-03 23:30:00 UTC),
+        datetime!(2025-01-04 00:30:00 UTC),
+        date!(2025 - 01 - 03),
+        &[(time!(09:00), time!(23:59:59))],
+        &[],
+        &chrono_tz::Tz::UTC,
+    ));
+}
+
+/* -------------------------------------------------------------------------- */
+/*                        DAYLIGHT SAVING TRANSITIONS                         */
+/* -------------------------------------------------------------------------- */
+#[test]
+fn test_ambiguous_local_times_follow_the_requested_occurrence() {
+    // London falls back at 02:00 BST on 2025-10-26, so 01:30 happens twice
+    let london = chrono_tz::Europe::London;
+    let local = datetime!(2025-10-26 01:30:00);
+
+    assert_eq!(
+        resolve_local_time(local, &london, AmbiguousTime::Earliest),
+        Some(datetime!(2025-10-26 00:30:00 UTC))
+    );
+    assert_eq!(
+        resolve_local_time(local, &london, AmbiguousTime::Latest),
+        Some(datetime!(2025-10-26 01:30:00 UTC))
+    );
+
+    // New York falls back at 02:00 EDT on 2025-11-02
+    let new_york = chrono_tz::America::New_York;
+    let local = datetime!(2025-11-02 01:30:00);
+
+    assert_eq!(
+        resolve_local_time(local, &new_york, AmbiguousTime::Earliest),
+        Some(datetime!(2025-11-02 05:30:00 UTC))
+    );
+    assert_eq!(
+        resolve_local_time(local, &new_york, AmbiguousTime::Latest),
+        Some(datetime!(2025-11-02 06:30:00 UTC))
+    );
+}
+
+#[test]
+fn test_skipped_local_times_shift_forward() {
+    // London jumps from 01:00 to 02:00 on 2025-03-30, 01:30 becomes 02:30 BST
+    assert_eq!(
+        resolve_local_time(
+            datetime!(2025-03-30 01:30:00),
+            &chrono_tz::Europe::London,
+            AmbiguousTime::Earliest
+        ),
+        Some(datetime!(2025-03-30 01:30:00 UTC))
+    );
+
+    // New York jumps from 02:00 to 03:00 on 2025-03-09, 02:30 becomes 03:30 EDT
+    assert_eq!(
+        resolve_local_time(
+            datetime!(2025-03-09 02:30:00),
+            &chrono_tz::America::New_York,
+            AmbiguousTime::Earliest
+        ),
+        Some(datetime!(2025-03-09 07:30:00 UTC))
+    );
+}
+
+#[test]
+fn test_slot_generation_across_dst_transitions() {
+    let hourly = SlotSettings {
+        duration: 60,
+        interval: 60,
+        buffer_before: 0,
+        buffer_after: 0,
+        capacity: 1,
+    };
+
+    // Spring forward in New York: 01:00 to 04:00 local is only two real hours
+    let slots = generate_slots(
+        datetime!(2025-03-09 01:00:00),
+        datetime!(2025-03-09 04:00:00),
+        &hourly,
+        &chrono_tz::America::New_York,
+        &[],
+    );
+    let starts: Vec<&str> = slots.iter().map(|s| s.start_time.as_str()).collect();
+    assert_eq!(starts, vec!["2025-03-09T06:00:00Z", "2025-03-09T07:00:00Z"]);
+
+    // Fall back in London: 00:00 to 03:00 local is four real hours, and the
+    // repeated hour is offered twice
+    let slots = generate_slots(
+        datetime!(2025-10-26 00:00:00),
+        datetime!(2025-10-26 03:00:00),
+        &hourly,
+        &chrono_tz::Europe::London,
+        &[],
+    );
+    let starts: Vec<&str> = slots.iter().map(|s| s.start_time.as_str()).collect();
+    assert_eq!(
+        starts,
+        vec![
+            "2025-10-25T23:00:00Z",
+            "2025-10-26T00:00:00Z",
+            "2025-10-26T01:00:00Z",
+            "2025-10-26T02:00:00Z"
+        ]
+    );
+
+    // Every slot still lasts exactly one hour
+    assert_eq!(slots[1].end_time, "2025-10-26T01:00:00Z");
+}
+
+#[test]
+fn test_bookings_inside_dst_transition_fit_opening_hours() {
+    let hours = vec![(time!(00:00), time!(03:00))];
+
+    // The second 01:00-02:00 in London is still inside 00:00-03:00
+    assert!(fits_opening_hours(
+        datetime!(2025-10-26 01:00:00 UTC),
+        datetime!(2025-10-26 02:00:00 UTC),
+        date!(2025 - 10 - 26),
+        &hours,
+        &[],
+        &chrono_tz::Europe::London,
+    ));
+
+    // New York loses 02:00-03:00 on its spring-forward day, so a 03:00 close
+    // comes after only two real hours and 03:00-04:00 EDT is outside
+    assert!(!fits_opening_hours(
+        datetime!(2025-03-09 07:00:00 UTC),
+        datetime!(2025-03-09 08:00:00 UTC),
+        date!(2025 - 03 - 09),
+        &hours,
+        &[],
+        &chrono_tz::America::New_York,
+    ));
+}
+
+/* -------------------------------------------------------------------------- */
+/*                           WEEKLY RULE VALIDATION                           */
+/* -------------------------------------------------------------------------- */
+fn day_rule(day_of_week: i32, open_time: &str, close_time: &str) -> DayTimeSlot {
+    DayTimeSlot {
+        day_of_week,
+        open_time: open_time.to_string(),
+        close_time: close_time.to_string(),
+        time_zone: None,
+    }
+}
+
+#[test]
+fn test_weekly_rules_report_every_bad_rule() {
+    let rules = vec![
+        day_rule(1, "09:00:00", "17:00:00"),
+        day_rule(8, "09:00:00", "17:00:00"),
+        day_rule(2, "9am", "17:00:00"),
+        day_rule(3, "10:00:00", "10:00:00"),
+    ];
+
+    let errors = validate_weekly_rules(&rules).unwrap_err();
+    let fields: Vec<(usize, &str)> = errors.iter().map(|e| (e.index, e.field.as_str())).collect();
+
+    assert_eq!(
+        fields,
+        vec![(1, "day_of_week"), (2, "open_time"), (3, "close_time")]
+    );
+}
+
+#[test]
+fn test_weekly_rules_merge_overlaps_on_the_same_day() {
+    let rules = vec![
+        day_rule(1, "13:00:00", "17:00:00"),
+        day_rule(1, "09:00:00", "12:00:00"),
+        day_rule(1, "11:00:00", "14:00:00"),
+        day_rule(2, "22:00:00", "02:00:00"),
+    ];
+
+    assert_eq!(
+        validate_weekly_rules(&rules).unwrap(),
+        vec![
+            (1, time!(09:00), time!(17:00)),
+            (2, time!(22:00), time!(02:00))
+        ]
+    );
+}
+
+#[test]
+fn test_weekly_rules_reject_overnight_spill_into_next_day() {
+    // Tuesday night runs into Wednesday morning hours
+    let rules = vec![
+        day_rule(2, "22:00:00", "04:00:00"),
+        day_rule(3, "03:00:00", "12:00:00"),
+    ];
+
+    let errors = validate_weekly_rules(&rules).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].index, 1);
+
+    // Sunday night wraps around to Monday
+    let rules = vec![
+        day_rule(1, "01:00:00", "09:00:00"),
+        day_rule(7, "23:00:00", "02:00:00"),
+    ];
+
+    assert!(validate_weekly_rules(&rules).is_err());
+}
+
+/* -------------------------------------------------------------------------- */
+/*                             SCHEDULED BREAKS                             */
+/* -------------------------------------------------------------------------- */
+#[test]
+fn test_breaks_block_slots_on_their_weekday_only() {
+    let lunch = BusinessBreak {
+        id: Uuid::new_v4(),
+        user_id: Uuid::nil(),
+        day_of_week: 3,
+        start_time: time!(13:00),
+        end_time: time!(14:00),
+        created_at: None,
+        updated_at: None,
+    };
+
+    // Tuesday to Thursday only yields the Wednesday break, in real UTC time
+    let periods = break_periods(
+        std::slice::from_ref(&lunch),
+        date!(2025 - 07 - 01),
+        date!(2025 - 07 - 03),
+        &chrono_tz::Europe::London,
+    );
+    assert_eq!(
+        periods,
+        vec![(
+            datetime!(2025-07-02 12:00:00 UTC),
+            datetime!(2025-07-02 13:00:00 UTC)
+        )]
+    );
+
+    let hourly = SlotSettings {
+        duration: 60,
+        interval: 60,
+        buffer_before: 0,
+        buffer_after: 0,
+        capacity: 1,
+    };
+
+    let slots = generate_slots(
+        datetime!(2025-07-02 12:00:00),
+        datetime!(2025-07-02 15:00:00),
+        &hourly,
+        &chrono_tz::Europe::London,
+        &periods,
+    );
+    let starts: Vec<&str> = slots.iter().map(|s| s.start_time.as_str()).collect();
+    assert_eq!(starts, vec!["2025-07-02T11:00:00Z", "2025-07-02T13:00:00Z"]);
+}
+
+/* -------------------------------------------------------------------------- */
+/*                   SERVICE AVAILABILITY (Intersection)                    */
+/* -------------------------------------------------------------------------- */
+#[test]
+fn test_service_hours_narrow_business_hours() {
+    let business = [(time!(09:00), time!(17:00))];
+    let service = [(time!(10:00), time!(12:00)), (time!(14:00), time!(20:00))];
+
+    assert_eq!(
+        intersect_opening_hours(&business, &[], &service, &[]),
+        (
+            vec![(time!(10:00), time!(12:00)), (time!(14:00), time!(17:00))],
+            vec![]
+        )
+    );
+
+    // A service not offered on this weekday closes the day for it
+    let (opening_hours, previous_day_hours) = intersect_opening_hours(&business, &[], &[], &[]);
+    assert!(opening_hours.is_empty() && previous_day_hours.is_empty());
+}
+
+#[test]
+fn test_service_hours_meet_overnight_business_hours() {
+    // Open from 20:00 the evening before until 02:00, the service only
+    // starts at midnight
+    let previous_business = [(time!(20:00), time!(02:00))];
+    let service = [(time!(00:00), time!(03:00))];
+
+    assert_eq!(
+        intersect_opening_hours(&[], &previous_business, &service, &[]),
+        (vec![(time!(00:00), time!(02:00))], vec![])
+    );
+
+    // Both sides overnight from the day before, the overlap still spills over
+    let previous_service = [(time!(21:00), time!(01:00))];
+
+    assert_eq!(
+        intersect_opening_hours(&[], &previous_business, &[], &previous_service),
+        (vec![], vec![(time!(21:00), time!(01:00))])
+    );
+}
+
+/* -------------------------------------------------------------------------- */
+/*                      STAFF (Any Staff Aggregation)                       */
+/* -------------------------------------------------------------------------- */
+#[test]
+fn test_any_staff_offers_each_start_once() {
+    let hourly = SlotSettings {
+        duration: 60,
+        interval: 60,
+        buffer_before: 0,
+        buffer_after: 0,
+        capacity: 1,
+    };
+
+    // Ada is booked at 10:00, Grace at 11:00
+    let mut slots = generate_slots(
+        datetime!(2025-01-01 09:00:00),
+        datetime!(2025-01-01 12:00:00),
+        &hourly,
+        &chrono_tz::UTC,
+        &[(
+            datetime!(2025-01-01 10:00:00 UTC),
+            datetime!(2025-01-01 11:00:00 UTC),
+        )],
+    );
+    slots.extend(generate_slots(
+        datetime!(2025-01-01 09:00:00),
+        datetime!(2025-01-01 12:00:00),
+        &hourly,
+        &chrono_tz::UTC,
+        &[(
+            datetime!(2025-01-01 11:00:00 UTC),
+            datetime!(2025-01-01 12:00:00 UTC),
+        )],
+    ));
+
+    merge_lane_slots(&mut slots);
+
+    let starts: Vec<&str> = slots.iter().map(|s| s.start_time.as_str()).collect();
+    assert_eq!(
+        starts,
+        vec![
+            "2025-01-01T09:00:00Z",
+            "2025-01-01T10:00:00Z",
+            "2025-01-01T11:00:00Z"
+        ]
+    );
+}
+
+#[test]
+fn test_running_classes_fill_before_other_lanes() {
+    let session = Some(Uuid::from_u128(50));
+
+    // Lanes come in staff name order, only Grace has the class running
+    let mut candidates = vec![("ada", None), ("grace", session), ("linus", None)];
+    order_lane_candidates(&mut candidates);
+
+    assert_eq!(
+        candidates,
+        vec![("grace", session), ("ada", None), ("linus", None)]
+    );
+}
+
+#[test]
+fn test_unassigned_bookings_block_every_lane() {
+    let haircut = Uuid::from_u128(1);
+    let ada = Some(Uuid::from_u128(10));
+    let grace = Some(Uuid::from_u128(11));
+    let nine = (
+        datetime!(2025-01-01 09:00:00 UTC),
+        datetime!(2025-01-01 10:00:00 UTC),
+    );
+
+    assert!(lanes_collide(None, ada));
+    assert!(lanes_collide(ada, None));
+    assert!(lanes_collide(ada, ada));
+    assert!(!lanes_collide(ada, grace));
+
+    // Held before the business had staff, so nobody takes 09:00
+    let holds = [SlotHold {
+        hold_token: Uuid::new_v4(),
+        business_id: Uuid::from_u128(100),
+        service_id: haircut,
+        staff_id: None,
+        start_time: nine.0,
+        end_time: nine.1,
+        blocked_start: nine.0,
+        blocked_end: nine.1,
+        expires_at: nine.0,
+        client_ip: None,
+    }];
+
+    assert_eq!(held_seats(&holds, ada, (haircut, 1), nine, nine), None);
+    assert_eq!(held_seats(&holds, grace, (haircut, 1), nine, nine), None);
+
+    // Slot listings block it as a plain period, it has no seat to share
+    assert!(hold_sessions(&holds, ada).is_empty());
+    assert_eq!(cross_lane_hold_periods(&holds, ada), vec![nine]);
+    assert!(cross_lane_hold_periods(&holds, None).is_empty());
+}
+
+/* -------------------------------------------------------------------------- */
+/*                         SLOT RANGES (One Schedule)                         */
+/* -------------------------------------------------------------------------- */
+#[test]
+fn test_schedule_hours_follow_each_date_of_a_range() {
+    let ada = Uuid::from_u128(10);
+
+    let schedule = OpeningSchedule {
+        // Wednesday 2025-03-05 is a holiday
+        overrides: HashMap::from([(
+            date!(2025 - 03 - 05),
+            vec![AvailabilityOverride {
+                id: Uuid::new_v4(),
+                user_id: Uuid::nil(),
+                override_date: date!(2025 - 03 - 05),
+                open_time: None,
+                close_time: None,
+                created_at: None,
+                updated_at: None,
+            }],
+        )]),
+        weekly_rules: HashMap::from([
+            (
+                1,
+                vec![AvailabilityRule {
+                    id: Uuid::new_v4(),
+                    user_id: Uuid::nil(),
+                    day_of_week: 1,
+                    open_time: time!(09:00),
+                    close_time: time!(17:00),
+                    created_at: None,
+                    updated_at: None,
+                }],
+            ),
+            (
+                2,
+                vec![AvailabilityRule {
+                    id: Uuid::new_v4(),
+                    user_id: Uuid::nil(),
+                    day_of_week: 2,
+                    open_time: time!(22:00),
+                    close_time: time!(02:00),
+                    created_at: None,
+                    updated_at: None,
+                }],
+            ),
+        ]),
+        service_rules: Vec::new(),
+        // Ada only works Monday afternoons
+        staff_rules: HashMap::from([(ada, vec![(1, time!(12:00), time!(20:00))])]),
+    };
+
+    assert_eq!(
+        schedule_hours(&schedule, None, date!(2025 - 03 - 03)),
+        (vec![(time!(09:00), time!(17:00))], vec![])
+    );
+    assert_eq!(
+        schedule_hours(&schedule, Some(ada), date!(2025 - 03 - 03)),
+        (vec![(time!(12:00), time!(17:00))], vec![])
+    );
+
+    // Closed for the holiday, Tuesday night still runs into it
+    assert_eq!(
+        schedule_hours(&schedule, None, date!(2025 - 03 - 05)),
+        (vec![], vec![(time!(22:00), time!(02:00))])
+    );
+    assert_eq!(
+        schedule_hours(&schedule, Some(ada), date!(2025 - 03 - 05)),
+        (vec![], vec![])
+    );
+}
+
+/* -------------------------------------------------------------------------- */
+/*                         RESOURCES (Shared Capacity)                        */
+/* -------------------------------------------------------------------------- */
+#[test]
+fn test_resource_is_blocked_only_when_full() {
+    // A room for two: bookings at 09-10 and 10-11 never share it,
+    // a third booking at 09:30-10:30 fills it from 09:30 to 10:30
+    let bookings = [
+        (
+            datetime!(2025-01-01 09:00:00 UTC),
+            datetime!(2025-01-01 10:00:00 UTC),
+        ),
+        (
+            datetime!(2025-01-01 10:00:00 UTC),
+            datetime!(2025-01-01 11:00:00 UTC),
+        ),
+    ];
+
+    assert!(saturated_periods(&bookings, 2).is_empty());
+
+    let mut bookings = bookings.to_vec();
+    bookings.push((
+        datetime!(2025-01-01 09:30:00 UTC),
+        datetime!(2025-01-01 10:30:00 UTC),
+    ));
+
+    assert_eq!(
+        saturated_periods(&bookings, 2),
+        vec![(
+            datetime!(2025-01-01 09:30:00 UTC),
+            datetime!(2025-01-01 10:30:00 UTC),
+        )]
+    );
+}
+
+/* -------------------------------------------------------------------------- */
+/*                       GROUP CLASSES (Seats Per Slot)                       */
+/* -------------------------------------------------------------------------- */
+#[test]
+fn test_group_session_offers_remaining_seats() {
+    let yoga = Uuid::from_u128(1);
+    let class = SlotSettings {
+        duration: 60,
+        interval: 30,
+        buffer_before: 0,
+        buffer_after: 0,
+        capacity: 10,
+    };
+
+    let mut slots = generate_slots(
+        datetime!(2025-01-01 09:00:00),
+        datetime!(2025-01-01 11:00:00),
+        &class,
+        &chrono_tz::UTC,
+        &[],
+    );
+
+    // Seven people are in the 09:00 class, the room is otherwise full then
+    let sessions = [GroupSession {
+        service_id: yoga,
+        start_time: datetime!(2025-01-01 09:00:00 UTC),
+        end_time: datetime!(2025-01-01 10:00:00 UTC),
+        blocked_period: (
+            datetime!(2025-01-01 09:00:00 UTC),
+            datetime!(2025-01-01 10:00:00 UTC),
+        ),
+        booked_seats: 7,
+    }];
+    let reserved = [(
+        datetime!(2025-01-01 09:00:00 UTC),
+        datetime!(2025-01-01 10:00:00 UTC),
+    )];
+
+    apply_group_sessions(&mut slots, &sessions, &reserved, yoga, &class);
+
+    let offered: Vec<(&str, i32)> = slots
+        .iter()
+        .map(|s| (s.start_time.as_str(), s.remaining_seats))
+        .collect();
+
+    // 09:30 would overlap the running class
+    assert_eq!(
+        offered,
+        vec![("2025-01-01T09:00:00Z", 3), ("2025-01-01T10:00:00Z", 10)]
+    );
+
+    // Another service cannot share the time with the class
+    let mut other_slots = generate_slots(
+        datetime!(2025-01-01 09:00:00),
+        datetime!(2025-01-01 10:00:00),
+        &class,
+        &chrono_tz::UTC,
+        &[],
+    );
+    apply_group_sessions(&mut other_slots, &sessions, &[], Uuid::from_u128(2), &class);
+    assert!(other_slots.is_empty());
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                 SLOT HOLDS                                 */
+/* -------------------------------------------------------------------------- */
+#[test]
+fn test_held_slots_are_blocked_for_others() {
+    let haircut = Uuid::from_u128(1);
+    let yoga = Uuid::from_u128(2);
+    let ada = Some(Uuid::from_u128(10));
+    let nine = (
+        datetime!(2025-01-01 09:00:00 UTC),
+        datetime!(2025-01-01 10:00:00 UTC),
+    );
+
+    let held_haircut = SlotHold {
+        hold_token: Uuid::new_v4(),
+        business_id: Uuid::from_u128(100),
+        service_id: haircut,
+        staff_id: ada,
+        start_time: nine.0,
+        end_time: nine.1,
+        blocked_start: nine.0,
+        blocked_end: nine.1,
+        expires_at: datetime!(2025-01-01 08:05:00 UTC),
+        client_ip: None,
+    };
+
+    // A one-to-one slot held by someone else is gone
+    let holds = [held_haircut.clone()];
+    assert_eq!(held_seats(&holds, ada, (haircut, 1), nine, nine), None);
+
+    // Another staff member is not affected
+    let grace = Some(Uuid::from_u128(11));
+    assert_eq!(held_seats(&holds, grace, (haircut, 1), nine, nine), Some(0));
+
+    // Holds on a class take one seat each, anything else overlapping keeps it
+    let held_yoga = SlotHold {
+        service_id: yoga,
+        ..held_haircut
+    };
+    let class_holds = [held_yoga.clone(), held_yoga];
+    assert_eq!(
+        held_seats(&class_holds, ada, (yoga, 10), nine, nine),
+        Some(2)
+    );
+    assert_eq!(
+        held_seats(&class_holds, ada, (haircut, 1), nine, nine),
+        None
+    );
+}
+
+#[test]
+fn test_holds_take_seats_in_slot_listings() {
+    let yoga = Uuid::from_u128(2);
+    let class = SlotSettings {
+        duration: 60,
+        interval: 60,
+        buffer_before: 0,
+        buffer_after: 0,
+        capacity: 3,
+    };
+
+    let mut slots = generate_slots(
+        datetime!(2025-01-01 09:00:00),
+        datetime!(2025-01-01 11:00:00),
+        &class,
+        &chrono_tz::UTC,
+        &[],
+    );
+
+    // Holds of other lanes are left out
+    let nine = SlotHold {
+        hold_token: Uuid::new_v4(),
+        business_id: Uuid::from_u128(100),
+        service_id: yoga,
+        staff_id: None,
+        start_time: datetime!(2025-01-01 09:00:00 UTC),
+        end_time: datetime!(2025-01-01 10:00:00 UTC),
+        blocked_start: datetime!(2025-01-01 09:00:00 UTC),
+        blocked_end: datetime!(2025-01-01 10:00:00 UTC),
+        expires_at: datetime!(2025-01-01 08:05:00 UTC),
+        client_ip: None,
+    };
+    let ten_with_ada = SlotHold {
+        staff_id: Some(Uuid::from_u128(10)),
+        start_time: datetime!(2025-01-01 10:00:00 UTC),
+        end_time: datetime!(2025-01-01 11:00:00 UTC),
+        blocked_start: datetime!(2025-01-01 10:00:00 UTC),
+        blocked_end: datetime!(2025-01-01 11:00:00 UTC),
+        ..nine.clone()
+    };
+    let holds = [nine, ten_with_ada];
+    apply_group_sessions(&mut slots, &hold_sessions(&holds, None), &[], yoga, &class);
+
+    let offered: Vec<i32> = slots.iter().map(|s| s.remaining_seats).collect();
+    assert_eq!(offered, vec![2, 3]);
+}
+
+#[test]
+fn test_each_client_keeps_one_hold_at_a_time() {
+    let holds = [SlotHold {
+        hold_token: Uuid::new_v4(),
+        business_id: Uuid::from_u128(100),
+        service_id: Uuid::from_u128(1),
+        staff_id: None,
+        start_time: datetime!(2025-01-01 09:00:00 UTC),
+        end_time: datetime!(2025-01-01 10:00:00 UTC),
+        blocked_start: datetime!(2025-01-01 09:00:00 UTC),
+        blocked_end: datetime!(2025-01-01 10:00:00 UTC),
+        expires_at: datetime!(2025-01-01 08:05:00 UTC),
+        client_ip: Some("203.0.113.7".to_string()),
+    }];
+
+    assert!(check_hold_limit(&holds, Some("203.0.113.7"), 50).is_err());
+    assert!(check_hold_limit(&holds, Some("198.51.100.2"), 50).is_ok());
+
+    // The business has room for one checkout only, it is taken
+    assert!(check_hold_limit(&holds, Some("198.51.100.2"), 1).is_err());
+}
+
+#[test]
+fn test_client_ip_only_trusts_forwarded_for_from_proxies() {
+    let proxy: IpAddr = "10.0.0.1".parse().unwrap();
+    let client: IpAddr = "203.0.113.7".parse().unwrap();
+
+    // A client connecting directly cannot pick its own address
+    assert_eq!(
+        client_ip(Some(client), Some("198.51.100.2"), &[proxy]),
+        Some("203.0.113.7".to_string())
+    );
+
+    // Behind the proxy, the hop it added is the client, not the spoofed one left of it
+    assert_eq!(
+        client_ip(Some(proxy), Some("198.51.100.2, 203.0.113.7"), &[proxy]),
+        Some("203.0.113.7".to_string())
+    );
+
+    // A proxy that sent nothing along is all there is to go on
+    assert_eq!(
+        client_ip(Some(proxy), None, &[proxy]),
+        Some("10.0.0.1".to_string())
+    );
+}
+
+/* -------------------------------------------------------------------------- */
+/*                              RECURRING SERIES                              */
+/* -------------------------------------------------------------------------- */
+#[test]
+fn test_series_starts_stop_at_count_or_until() {
+    let first = datetime!(2025-03-03 10:00:00);
+
+    // A fixed number of weekly occurrences
+    let weekly = series_local_starts(first, 1, Some(3), None, 52);
+    assert_eq!(
+        weekly,
+        vec![
+            datetime!(2025-03-03 10:00:00),
+            datetime!(2025-03-10 10:00:00),
+            datetime!(2025-03-17 10:00:00),
+        ]
+    );
+
+    // Every other week up to and including the end date
+    let biweekly = series_local_starts(first, 2, None, Some(date!(2025 - 03 - 31)), 52);
+    assert_eq!(
+        biweekly,
+        vec![
+            datetime!(2025-03-03 10:00:00),
+            datetime!(2025-03-17 10:00:00),
+            datetime!(2025-03-31 10:00:00),
+        ]
+    );
+
+    // One past the limit, so callers can tell the series is too long
+    assert_eq!(series_local_starts(first, 1, Some(100), None, 52).len(), 53);
+}
+
+#[test]
+fn test_series_recurrence_skips_failed_occurrences() {
+    let recurrence = series_recurrence(
+        1,
+        datetime!(2025-03-17 10:00:00 +1),
+        &[datetime!(2025-03-10 10:00:00)],
+        "Africa/Lagos",
+    );
+
+    assert_eq!(
+        recurrence,
+        vec![
+            "RRULE:FREQ=WEEKLY;INTERVAL=1;UNTIL=20250317T090000Z".to_string(),
+            "EXDATE;TZID=Africa/Lagos:20250310T100000".to_string(),
+        ]
+    );
+
+    // Each occurrence points at its own instance of the event
+    assert_eq!(
+        recurring_instance_id("abc123", datetime!(2025-03-10 10:00:00 +1)),
+        "abc123_20250310T090000Z"
+    );
+}
+
+#[test]
+fn test_cancelled_series_ends_before_the_occurrence() {
+    let recurrence = vec![
+        "RRULE:FREQ=WEEKLY;INTERVAL=2;UNTIL=20250428T090000Z".to_string(),
+        "EXDATE;TZID=Africa/Lagos:20250310T100000".to_string(),
+    ];
+
+    // Cancelling from 2025-03-31 on keeps the instances before it only
+    assert_eq!(
+        end_recurrence_before(&recurrence, datetime!(2025-03-31 10:00:00 +1)),
+        vec![
+            "RRULE:FREQ=WEEKLY;INTERVAL=2;UNTIL=20250331T085959Z".to_string(),
+            "EXDATE;TZID=Africa/Lagos:20250310T100000".to_string(),
+        ]
+    );
+
+    // A count would outlive the new end, it goes
+    assert_eq!(
+        end_recurrence_before(
+            &["RRULE:FREQ=WEEKLY;COUNT=10".to_string()],
+            datetime!(2025-03-31 09:00:00 UTC)
+        ),
+        vec!["RRULE:FREQ=WEEKLY;UNTIL=20250331T085959Z".to_string()]
+    );
+}
+
+/* -------------------------------------------------------------------------- */
+/*                                  WAITLIST                                  */
+/* -------------------------------------------------------------------------- */
+#[test]
+fn test_waitlist_openings_use_local_dates_ahead() {
+    let now = datetime!(2025-06-01 12:00:00 UTC);
+
+    let openings = waitlist_openings(
+        &[
+            datetime!(2025-06-01 09:00:00 UTC), // Already over
+            datetime!(2025-06-02 23:30:00 UTC), // Past midnight in Lagos
+            datetime!(2025-06-02 23:30:00 UTC), // A second seat of the class
+        ],
+        now,
+        "Africa/Lagos",
+    );
+
+    assert_eq!(
+        openings,
+        vec![(datetime!(2025-06-02 23:30:00 UTC), date!(2025 - 06 - 03), 2)]
+    );
+}
+
+#[test]
+fn test_waitlist_tells_one_entry_per_seat_in_join_order() {
+    let ada = WaitlistEntry {
+        id: Uuid::from_u128(1),
+        business_id: Uuid::from_u128(100),
+        service_id: Uuid::from_u128(2),
+        customer_name: "Ada".to_string(),
+        customer_email: Some("ada@example.com".to_string()),
+        customer_phone: None,
+        preferred_from: date!(2025 - 06 - 01),
+        preferred_to: date!(2025 - 06 - 30),
+        // Told about an earlier opening she did not get
+        notified_at: Some(datetime!(2025-06-01 08:00:00 UTC)),
+        created_at: Some(datetime!(2025-05-01 10:00:00 UTC)),
+    };
+    let grace = WaitlistEntry {
+        id: Uuid::from_u128(2),
+        customer_name: "Grace".to_string(),
+        notified_at: None,
+        created_at: Some(datetime!(2025-05-02 10:00:00 UTC)),
+        ..ada.clone()
+    };
+    let linus = WaitlistEntry {
+        id: Uuid::from_u128(3),
+        customer_name: "Linus".to_string(),
+        notified_at: None,
+        created_at: Some(datetime!(2025-05-03 10:00:00 UTC)),
+        ..ada.clone()
+    };
+    let late_june = WaitlistEntry {
+        id: Uuid::from_u128(4),
+        preferred_from: date!(2025 - 06 - 20),
+        created_at: Some(datetime!(2025-04-01 10:00:00 UTC)),
+        ..grace.clone()
+    };
+    let entries = [linus, late_june, grace, ada];
+
+    let names = |recipients: Vec<&WaitlistEntry>| -> Vec<String> {
+        recipients
+            .into_iter()
+            .map(|entry| entry.customer_name.clone())
+            .collect()
+    };
+
+    // Two seats freed on the 10th, the two who joined first hear about them
+    let mut notified = HashSet::new();
+    assert_eq!(
+        names(waitlist_recipients(
+            &entries,
+            date!(2025 - 06 - 10),
+            2,
+            &notified
+        )),
+        vec!["Ada", "Grace"]
+    );
+
+    // Already told in this round, the next in line gets the other opening
+    notified.insert(Uuid::from_u128(1));
+    assert_eq!(
+        names(waitlist_recipients(
+            &entries,
+            date!(2025 - 06 - 10),
+            1,
+            &notified
+        )),
+        vec!["Grace"]
+    );
+}
+
+/* -------------------------------------------------------------------------- */
+/*                              IDEMPOTENCY KEYS                              */
+/* -------------------------------------------------------------------------- */
+#[test]
+fn test_idempotency_key_replays_only_the_same_payload() {
+    let payload = serde_json::json!({ "service_id": "a", "customer_name": "Ada" });
+    let response = serde_json::json!({ "success": true, "data": { "id": "b" } });
+
+    let record = |response: Option<serde_json::Value>| IdempotencyRecord {
+        payload: payload.clone(),
+        response,
+    };
+
+    // Key order does not make a payload different
+    let reordered = serde_json::json!({ "customer_name": "Ada", "service_id": "a" });
+    assert!(matches!(
+        idempotency_outcome(record(Some(response.clone())), &reordered),
+        IdempotencyOutcome::Replay(replayed) if replayed == response
+    ));
+
+    assert!(matches!(
+        idempotency_outcome(record(None), &payload),
+        IdempotencyOutcome::InProgress
+    ));
+
+    let changed = serde_json::json!({ "service_id": "a", "customer_name": "Bob" });
+    assert!(matches!(
+        idempotency_outcome(record(Some(response)), &changed),
+        IdempotencyOutcome::PayloadMismatch
+    ));
+}
+
+/* -------------------------------------------------------------------------- */
+/*                        APPOINTMENT STATUS LIFECYCLE                        */
+/* -------------------------------------------------------------------------- */
+#[test]
+fn test_status_transitions_are_enforced() {
+    use AppointmentStatus::*;
+
+    // The happy path
+    assert!(Pending.can_transition_to(Confirmed));
+    assert!(Confirmed.can_transition_to(Completed));
+    assert!(Confirmed.can_transition_to(NoShow));
+    assert!(Pending.can_transition_to(Cancelled));
+
+    // Final states stay final
+    assert!(!Completed.can_transition_to(Cancelled));
+    assert!(!NoShow.can_transition_to(Confirmed));
+    assert!(!Cancelled.can_transition_to(Confirmed));
+
+    // No going backwards
+    assert!(!Confirmed.can_transition_to(Pending));
+}
+
+#[test]
+fn test_only_pending_and_confirmed_block_slots() {
+    assert!(AppointmentStatus::Pending.is_active());
+    assert!(AppointmentStatus::Confirmed.is_active());
+    assert!(!AppointmentStatus::Completed.is_active());
+    assert!(!AppointmentStatus::NoShow.is_active());
+    assert!(!AppointmentStatus::Cancelled.is_active());
+}
+
+#[test]
+fn test_status_round_trips_through_db_string() {
+    for status in [
+        AppointmentStatus::Pending,
+        AppointmentStatus::Confirmed,
+        AppointmentStatus::Completed,
+        AppointmentStatus::NoShow,
+        AppointmentStatus::Cancelled,
+    ] {
+        assert_eq!(AppointmentStatus::from_str(status.as_str()), Ok(status));
+
+        // The JSON value must match the value stored in the DB
+        let json_output = serde_json::to_value(status).expect("Failed to serialize");
+        assert_eq!(json_output, status.as_str());
+    }
+
+    assert!(AppointmentStatus::from_str("archived").is_err());
+}
@@ -1,4 +1,6 @@
-use crate::routes::{appointment_routes, service_routes, user_routes};
+use crate::routes::{
+    appointment_routes, resource_routes, service_routes, staff_routes, user_routes, waitlist_routes,
+};
 use crate::structs::{db_struct, response_struct, util_struct};
 use utoipa::OpenApi;
 
@@ -7,18 +9,53 @@ use utoipa::OpenApi;
     paths(
         // Appointments
         appointment_routes::create_appointment,
+        appointment_routes::create_appointment_series,
+        appointment_routes::create_slot_hold,
         appointment_routes::get_all_appointments,
         appointment_routes::get_appointment_by_id,
+        appointment_routes::cancel_appointment,
+        appointment_routes::reschedule_appointment,
+        appointment_routes::confirm_appointment,
+        appointment_routes::complete_appointment,
+        appointment_routes::mark_appointment_no_show,
 
         // Services
         service_routes::create_service,
         service_routes::get_all_services,
         service_routes::get_service_by_id,
+        service_routes::get_service_availability,
+        service_routes::set_service_availability,
         // service_routes::update_service, // Add these if you annotated them
         // service_routes::delete_service,
 
+        // Staff
+        staff_routes::create_staff,
+        staff_routes::get_all_staff,
+        staff_routes::update_staff,
+        staff_routes::get_staff_availability,
+        staff_routes::set_staff_availability,
+
+        // Resources
+        resource_routes::create_resource,
+        resource_routes::get_all_resources,
+        resource_routes::update_resource,
+        resource_routes::delete_resource,
+
+        // Waitlist
+        waitlist_routes::create_waitlist_entry,
+        waitlist_routes::get_waitlist,
+        waitlist_routes::delete_waitlist_entry,
+
         // Users
         user_routes::get_available_slots,
+        user_routes::get_available_slots_range,
+        user_routes::get_next_available_slots,
+        user_routes::get_breaks,
+        user_routes::set_breaks,
+        user_routes::get_availability_overrides,
+        user_routes::create_availability_override,
+        user_routes::update_availability_override,
+        user_routes::delete_availability_override,
         // user_routes::get_me, // Add these if you annotated them
     ),
     components(
@@ -26,20 +63,58 @@ use utoipa::OpenApi;
             // Request/Response bodies
             db_struct::CreateAppointment,
             db_struct::Appointment,
+            db_struct::AppointmentStatus,
+            db_struct::CancelAppointment,
+            db_struct::CancelScope,
+            db_struct::CreateAppointmentSeries,
+            db_struct::RecurrenceRule,
+            db_struct::RecurrenceFrequency,
+            db_struct::AppointmentSeries,
+            db_struct::AppointmentSeriesResult,
+            db_struct::FailedOccurrence,
+            db_struct::RescheduleAppointment,
             db_struct::Service,
             db_struct::CreateService,
             db_struct::UpdateService,
+            db_struct::ServiceAvailabilityRule,
+            db_struct::SetServiceAvailability,
+            db_struct::AvailabilityOverride,
+            db_struct::CreateAvailabilityOverride,
+            db_struct::SetAvailabilityOverride,
+            db_struct::OverrideWindow,
+            db_struct::BusinessBreak,
+            db_struct::SetBreaks,
+            db_struct::BreakWindow,
+            db_struct::Staff,
+            db_struct::StaffWithServices,
+            db_struct::CreateStaff,
+            db_struct::UpdateStaff,
+            db_struct::StaffAvailabilityRule,
+            db_struct::SetStaffAvailability,
+            db_struct::Resource,
+            db_struct::ResourceWithServices,
+            db_struct::CreateResource,
+            db_struct::UpdateResource,
+            db_struct::WaitlistEntry,
+            db_struct::CreateWaitlistEntry,
             util_struct::TimeSlot,
+            util_struct::CreateSlotHold,
+            util_struct::SlotHold,
+            util_struct::DaySlots,
 
             // Generic wrappers (Aliased for documentation)
             response_struct::ApiResponse<db_struct::Appointment>,
             response_struct::ApiResponse<Vec<db_struct::Service>>,
             response_struct::ApiResponse<Vec<util_struct::TimeSlot>>,
+            response_struct::ApiResponse<Vec<util_struct::DaySlots>>,
         )
     ),
     tags(
         (name = "Appointments", description = "Booking management"),
         (name = "Services", description = "Service catalog management"),
+        (name = "Staff", description = "Staff members and their working hours"),
+        (name = "Resources", description = "Rooms, equipment and other shared capacity"),
+        (name = "Waitlist", description = "Customers waiting for an opening"),
         (name = "Users", description = "User profile and availability")
     )
 )]
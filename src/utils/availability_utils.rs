@@ -0,0 +1,354 @@
+use crate::{
+    structs::{
+        db_struct::{
+            AvailabilityOverride, AvailabilityRule, BusinessBreak, ServiceAvailabilityRule, Staff,
+            StaffAvailabilityRule,
+        },
+        util_struct::{OpeningSchedule, SlotHold},
+    },
+    utils::others_utils::{
+        OpeningWindow, break_periods, convert_to_local_primitive, saturated_periods, schedule_hours,
+    },
+};
+use chrono_tz::Tz;
+use sqlx::PgConnection;
+use std::collections::{HashMap, HashSet};
+use time::{Date, Duration, OffsetDateTime};
+use uuid::Uuid;
+
+pub async fn business_time_zone(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+) -> Result<String, sqlx::Error> {
+    sqlx::query_scalar!("SELECT time_zone FROM users WHERE id = $1", user_id)
+        .fetch_one(conn)
+        .await
+}
+
+// Overrides from the day before `from` through `to`, since overnight windows
+// of the day before spill into the first date, and all the weekly rules
+pub async fn load_opening_schedule(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    service_id: Uuid,
+    staff_ids: &[Uuid],
+    (from, to): (Date, Date),
+) -> Result<OpeningSchedule, sqlx::Error> {
+    let overrides = sqlx::query_as!(
+        AvailabilityOverride,
+        r#"
+        SELECT * FROM availability_overrides
+        WHERE user_id = $1 AND override_date BETWEEN $2 AND $3
+        ORDER BY open_time
+        "#,
+        user_id,
+        from.previous_day().unwrap_or(from),
+        to
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let weekly_rules = sqlx::query_as!(
+        AvailabilityRule,
+        r#"
+        SELECT * FROM business_availability
+        WHERE user_id = $1
+        ORDER BY open_time
+        "#,
+        user_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let service_rules = sqlx::query_as!(
+        ServiceAvailabilityRule,
+        r#"
+        SELECT * FROM service_availability
+        WHERE service_id = $1
+        ORDER BY open_time
+        "#,
+        service_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let staff_rules = sqlx::query_as!(
+        StaffAvailabilityRule,
+        r#"
+        SELECT * FROM staff_availability
+        WHERE staff_id = ANY($1)
+        ORDER BY open_time
+        "#,
+        staff_ids
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut schedule = OpeningSchedule {
+        overrides: HashMap::new(),
+        weekly_rules: HashMap::new(),
+        service_rules: service_rules
+            .into_iter()
+            .map(|rule| (rule.day_of_week, rule.open_time, rule.close_time))
+            .collect(),
+        staff_rules: HashMap::new(),
+    };
+
+    for item in overrides {
+        schedule
+            .overrides
+            .entry(item.override_date)
+            .or_default()
+            .push(item);
+    }
+
+    for rule in weekly_rules {
+        schedule
+            .weekly_rules
+            .entry(rule.day_of_week)
+            .or_default()
+            .push(rule);
+    }
+
+    for rule in staff_rules {
+        schedule
+            .staff_rules
+            .entry(rule.staff_id)
+            .or_default()
+            .push((rule.day_of_week, rule.open_time, rule.close_time));
+    }
+
+    Ok(schedule)
+}
+
+// Opening hours of a single date for one booking lane
+pub async fn opening_hours_for_booking(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    service_id: Uuid,
+    staff_id: Option<Uuid>,
+    date: Date,
+) -> Result<(Vec<OpeningWindow>, Vec<OpeningWindow>), sqlx::Error> {
+    let staff_ids: Vec<Uuid> = staff_id.into_iter().collect();
+    let schedule =
+        load_opening_schedule(conn, user_id, service_id, &staff_ids, (date, date)).await?;
+
+    Ok(schedule_hours(&schedule, staff_id, date))
+}
+
+// Who can take a booking of the service: every active staff member offering
+// it, or the business itself (`None`) as long as it has no staff at all
+pub async fn booking_lanes(
+    conn: &mut PgConnection,
+    business_id: Uuid,
+    service_id: Uuid,
+) -> Result<Vec<Option<Staff>>, sqlx::Error> {
+    let has_staff = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM staff WHERE business_id = $1 AND is_active
+        ) as "has_staff!: bool"
+        "#,
+        business_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if !has_staff {
+        return Ok(vec![None]);
+    }
+
+    let staff = sqlx::query_as!(
+        Staff,
+        r#"
+        SELECT st.* FROM staff st
+        JOIN staff_services ss ON ss.staff_id = st.id
+        WHERE st.business_id = $1 AND ss.service_id = $2 AND st.is_active
+        ORDER BY st.name, st.id
+        "#,
+        business_id,
+        service_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(staff.into_iter().map(Some).collect())
+}
+
+// Staff without a calendar of their own share the primary one, where the
+// bookings of the others show up as busy too. Those block their own lane only.
+pub async fn shared_calendar_bookings(
+    conn: &mut PgConnection,
+    staff: Option<&Staff>,
+    (start_time, end_time): (OffsetDateTime, OffsetDateTime),
+) -> Result<Vec<(OffsetDateTime, OffsetDateTime)>, sqlx::Error> {
+    let Some(staff) = staff.filter(|staff| staff.google_calendar_id.is_none()) else {
+        return Ok(Vec::new());
+    };
+
+    let bookings = sqlx::query!(
+        r#"
+        SELECT a.appointment_start_time, a.appointment_end_time
+        FROM appointments a
+        JOIN staff st ON st.id = a.staff_id
+        WHERE st.business_id = $1
+        AND st.id <> $2
+        AND st.google_calendar_id IS NULL
+        AND a.status IN ('pending', 'confirmed')
+        AND a.appointment_start_time < $4
+        AND a.appointment_end_time > $3
+        "#,
+        staff.business_id,
+        staff.id,
+        start_time,
+        end_time
+    )
+    .fetch_all(conn)
+    .await?;
+
+    Ok(bookings
+        .into_iter()
+        .map(|booking| (booking.appointment_start_time, booking.appointment_end_time))
+        .collect())
+}
+
+// Breaks touching the UTC window, a day of margin on each side covers any offset
+pub async fn breaks_between(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    utc_window_start: OffsetDateTime,
+    utc_window_end: OffsetDateTime,
+    tz: &Tz,
+) -> Result<Vec<(OffsetDateTime, OffsetDateTime)>, sqlx::Error> {
+    let breaks = sqlx::query_as!(
+        BusinessBreak,
+        "SELECT * FROM business_breaks WHERE user_id = $1",
+        user_id
+    )
+    .fetch_all(conn)
+    .await?;
+
+    if breaks.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let local_date = |instant: OffsetDateTime| {
+        convert_to_local_primitive(instant, tz.name())
+            .map(|local| local.date())
+            .unwrap_or(instant.date())
+    };
+
+    let from = local_date(utc_window_start - Duration::days(1));
+    let to = local_date(utc_window_end + Duration::days(1));
+
+    Ok(break_periods(&breaks, from, to, tz)
+        .into_iter()
+        .filter(|(start, end)| *start < utc_window_end && *end > utc_window_start)
+        .collect())
+}
+
+// Times when a resource the service needs is reserved up to its capacity.
+// Holds reserve what their service needs like bookings do.
+pub async fn resource_blocked_periods(
+    conn: &mut PgConnection,
+    service_id: Uuid,
+    (utc_window_start, utc_window_end): (OffsetDateTime, OffsetDateTime),
+    exclude_appointment_id: Option<Uuid>,
+    holds: &[SlotHold],
+) -> Result<Vec<(OffsetDateTime, OffsetDateTime)>, sqlx::Error> {
+    // The seats of a group session share what the session holds
+    let reservations = sqlx::query!(
+        r#"
+        SELECT DISTINCT ON (r.id, COALESCE(a.session_id, a.id))
+            r.id as resource_id,
+            r.capacity,
+            a.service_id,
+            a.staff_id,
+            a.appointment_start_time,
+            a.session_id,
+            a.appointment_start_time - make_interval(mins => s.buffer_before_minutes) as "blocked_start!",
+            a.appointment_end_time + make_interval(mins => s.buffer_after_minutes) as "blocked_end!"
+        FROM service_resources sr
+        JOIN resources r ON r.id = sr.resource_id
+        JOIN appointment_resources ar ON ar.resource_id = r.id
+        JOIN appointments a ON a.id = ar.appointment_id
+        JOIN services s ON s.id = a.service_id
+        WHERE sr.service_id = $1
+        AND a.status IN ('pending', 'confirmed')
+        AND a.appointment_end_time + make_interval(mins => s.buffer_after_minutes) > $2
+        AND a.appointment_start_time - make_interval(mins => s.buffer_before_minutes) < $3
+        AND ($4::UUID IS NULL OR a.id <> $4)
+        "#,
+        service_id,
+        utc_window_start,
+        utc_window_end,
+        exclude_appointment_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut by_resource: HashMap<Uuid, (i32, Vec<(OffsetDateTime, OffsetDateTime)>)> =
+        HashMap::new();
+
+    // A hold joining a running session, or one already counted, needs nothing more
+    let mut counted_sessions = HashSet::new();
+
+    for reservation in reservations {
+        if reservation.session_id.is_some() {
+            counted_sessions.insert((
+                reservation.service_id,
+                reservation.staff_id,
+                reservation.appointment_start_time,
+            ));
+        }
+
+        by_resource
+            .entry(reservation.resource_id)
+            .or_insert((reservation.capacity, Vec::new()))
+            .1
+            .push((reservation.blocked_start, reservation.blocked_end));
+    }
+
+    let holds: Vec<&SlotHold> = holds
+        .iter()
+        .filter(|hold| hold.blocked_end > utc_window_start && hold.blocked_start < utc_window_end)
+        .collect();
+
+    if !holds.is_empty() {
+        // Every service sharing a resource with this one, and that resource
+        let shared_resources = sqlx::query!(
+            r#"
+            SELECT other.service_id, r.id as resource_id, r.capacity
+            FROM service_resources sr
+            JOIN resources r ON r.id = sr.resource_id
+            JOIN service_resources other ON other.resource_id = r.id
+            WHERE sr.service_id = $1
+            "#,
+            service_id
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        for hold in holds {
+            if !counted_sessions.insert((hold.service_id, hold.staff_id, hold.start_time)) {
+                continue;
+            }
+
+            for resource in shared_resources
+                .iter()
+                .filter(|resource| resource.service_id == hold.service_id)
+            {
+                by_resource
+                    .entry(resource.resource_id)
+                    .or_insert((resource.capacity, Vec::new()))
+                    .1
+                    .push((hold.blocked_start, hold.blocked_end));
+            }
+        }
+    }
+
+    Ok(by_resource
+        .into_values()
+        .flat_map(|(capacity, periods)| saturated_periods(&periods, capacity as usize))
+        .collect())
+}
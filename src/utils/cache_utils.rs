@@ -0,0 +1,160 @@
+use crate::utils::{availability_utils::business_time_zone, others_utils::local_dates};
+use deadpool_redis::redis;
+use sqlx::PgPool;
+use time::{Date, OffsetDateTime};
+use uuid::Uuid;
+
+const SLOT_CACHE_TTL_SECONDS: u64 = 60 * 5;
+
+pub fn slot_cache_key(
+    business_id: Uuid,
+    date: Date,
+    service_id: Uuid,
+    staff_id: Option<Uuid>,
+) -> String {
+    let staff = staff_id.map_or("any".to_string(), |id| id.to_string());
+
+    format!("slots:{}:{}:{}:{}", business_id, date, service_id, staff)
+}
+
+// Every cached key of a business is listed in its index, so invalidating
+// never has to walk the whole keyspace
+fn slot_index_key(business_id: Uuid) -> String {
+    format!("slot_keys:{}", business_id)
+}
+
+pub async fn cache_slots(
+    conn: &mut deadpool_redis::Connection,
+    business_id: Uuid,
+    cache_key: &str,
+    json: String,
+) -> redis::RedisResult<()> {
+    let index_key = slot_index_key(business_id);
+
+    // The index outlives each of its keys, dead ones are dropped on invalidation
+    redis::pipe()
+        .atomic()
+        .cmd("SET")
+        .arg(cache_key)
+        .arg(json)
+        .arg("EX")
+        .arg(SLOT_CACHE_TTL_SECONDS)
+        .ignore()
+        .cmd("SADD")
+        .arg(&index_key)
+        .arg(cache_key)
+        .ignore()
+        .cmd("EXPIRE")
+        .arg(&index_key)
+        .arg(SLOT_CACHE_TTL_SECONDS)
+        .ignore()
+        .query_async(conn)
+        .await
+}
+
+// Bookings are stored in UTC while the keys use the business-local date.
+// A booking also blocks other services through its staff lane, its resources
+// or, left unassigned, every lane, so each service loses those dates.
+pub async fn invalidate_booked_slot_cache(
+    redis_pool: &deadpool_redis::Pool,
+    pool: &PgPool,
+    business_id: Uuid,
+    starts: &[OffsetDateTime],
+) {
+    let time_zone = match pool.acquire().await {
+        Ok(mut conn) => business_time_zone(&mut conn, business_id).await.ok(),
+        Err(_) => None,
+    };
+
+    match time_zone {
+        Some(time_zone) => {
+            let dates = local_dates(starts, &time_zone);
+            invalidate_business_slot_cache(redis_pool, business_id, &dates).await;
+        }
+
+        // Without the zone the local dates are unknown, drop the whole business
+        None => invalidate_all_business_slot_cache(redis_pool, business_id).await,
+    }
+}
+
+// A service schedule change touches every cached date of that service
+pub async fn invalidate_all_service_slot_cache(
+    redis_pool: &deadpool_redis::Pool,
+    business_id: Uuid,
+    service_id: Uuid,
+) {
+    if let Ok(mut conn) = redis_pool.get().await {
+        let service = service_id.to_string();
+        delete_slot_keys(&mut conn, business_id, |_, service_key| {
+            service_key == service
+        })
+        .await;
+    }
+}
+
+// Opening hours changes affect every service of the business
+pub async fn invalidate_business_slot_cache(
+    redis_pool: &deadpool_redis::Pool,
+    business_id: Uuid,
+    dates: &[Date],
+) {
+    if let Ok(mut conn) = redis_pool.get().await {
+        let dates: Vec<String> = dates.iter().map(|date| date.to_string()).collect();
+        delete_slot_keys(&mut conn, business_id, |date, _| {
+            dates.iter().any(|d| d == date)
+        })
+        .await;
+    }
+}
+
+// Weekly changes touch every cached date of the business
+pub async fn invalidate_all_business_slot_cache(
+    redis_pool: &deadpool_redis::Pool,
+    business_id: Uuid,
+) {
+    if let Ok(mut conn) = redis_pool.get().await {
+        delete_slot_keys(&mut conn, business_id, |_, _| true).await;
+    }
+}
+
+// Indexed keys of the business whose date and service pass the filter
+async fn delete_slot_keys(
+    conn: &mut deadpool_redis::Connection,
+    business_id: Uuid,
+    matches: impl Fn(&str, &str) -> bool,
+) {
+    let index_key = slot_index_key(business_id);
+
+    let keys: Vec<String> = redis::cmd("SMEMBERS")
+        .arg(&index_key)
+        .query_async(&mut *conn)
+        .await
+        .unwrap_or_default();
+
+    let matching: Vec<String> = keys
+        .into_iter()
+        .filter(|key| {
+            // slots:{business}:{date}:{service}:{staff}
+            let mut parts = key.split(':').skip(2);
+            matches(
+                parts.next().unwrap_or_default(),
+                parts.next().unwrap_or_default(),
+            )
+        })
+        .collect();
+
+    for chunk in matching.chunks(100) {
+        let _: () = redis::pipe()
+            .atomic()
+            .cmd("DEL")
+            .arg(chunk)
+            .ignore()
+            .cmd("SREM")
+            .arg(&index_key)
+            .arg(chunk)
+            .ignore()
+            .query_async(&mut *conn)
+            .await
+            .unwrap_or(());
+    }
+}
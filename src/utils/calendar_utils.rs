@@ -0,0 +1,246 @@
+use crate::structs::{
+    db_struct::{
+        GoogleCalendarEvent, GoogleEventItem, GoogleEventItemTime, GoogleEventList,
+        GoogleEventRecurrencePatch, GoogleEventTimesPatch, Staff,
+    },
+    util_struct::{FreeBusyRequest, FreeBusyRequestItem, FreeBusyResponse},
+};
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+use uuid::Uuid;
+
+pub const PRIMARY_CALENDAR: &str = "primary";
+
+// Where a booking lane's busy time lives. Staff without a calendar of their
+// own fall back to the primary one, which holds the bookings of them all.
+pub fn busy_calendar(staff: Option<&Staff>) -> &str {
+    staff
+        .and_then(|staff| staff.google_calendar_id.as_deref())
+        .unwrap_or(PRIMARY_CALENDAR)
+}
+
+fn events_url(calendar_id: &str) -> String {
+    format!(
+        "https://www.googleapis.com/calendar/v3/calendars/{}/events",
+        calendar_id
+    )
+}
+
+async fn parse_event_response(res: reqwest::Response) -> Result<GoogleEventItem, String> {
+    if !res.status().is_success() {
+        // Google returned an error, maybe a 400 - Bad Request
+        let err_text = res.text().await.unwrap_or_default();
+        return Err(format!("Google Calendar API Error: {}", err_text));
+    }
+
+    res.json::<GoogleEventItem>()
+        .await
+        .map_err(|e| format!("Failed to parse Google event: {}", e))
+}
+
+pub async fn create_calendar_event(
+    client: &reqwest::Client,
+    access_token: &str,
+    calendar_id: &str,
+    event: &GoogleCalendarEvent,
+) -> Result<GoogleEventItem, String> {
+    let res = client
+        .post(format!("{}?sendUpdates=all", events_url(calendar_id)))
+        .bearer_auth(access_token)
+        .json(event)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to contact Google: {}", e))?;
+
+    parse_event_response(res).await
+}
+
+// Events we create carry the appointment id as a private extended property,
+// bookings made before the event id was stored are only found through it
+pub async fn find_appointment_event_id(
+    client: &reqwest::Client,
+    access_token: &str,
+    calendar_id: &str,
+    appointment_id: Uuid,
+) -> Result<Option<String>, String> {
+    let res = client
+        .get(events_url(calendar_id))
+        .bearer_auth(access_token)
+        .query(&[(
+            "privateExtendedProperty",
+            format!("appointment_id={}", appointment_id),
+        )])
+        .send()
+        .await
+        .map_err(|e| format!("Failed to contact Google: {}", e))?;
+
+    if !res.status().is_success() {
+        let err_text = res.text().await.unwrap_or_default();
+        return Err(format!("Google Calendar API Error: {}", err_text));
+    }
+
+    let events: GoogleEventList = res.json().await.map_err(|e| e.to_string())?;
+
+    Ok(events.items.into_iter().next().map(|event| event.id))
+}
+
+pub async fn get_calendar_event(
+    client: &reqwest::Client,
+    access_token: &str,
+    calendar_id: &str,
+    event_id: &str,
+) -> Result<GoogleEventItem, String> {
+    let res = client
+        .get(format!("{}/{}", events_url(calendar_id), event_id))
+        .bearer_auth(access_token)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to contact Google: {}", e))?;
+
+    parse_event_response(res).await
+}
+
+// None when the event no longer matches the etag it was patched against
+pub async fn patch_calendar_event_times(
+    client: &reqwest::Client,
+    access_token: &str,
+    calendar_id: &str,
+    event_id: &str,
+    etag: Option<&str>,
+    times: &GoogleEventTimesPatch,
+) -> Result<Option<GoogleEventItem>, String> {
+    let mut request = client
+        .patch(format!(
+            "{}/{}?sendUpdates=all",
+            events_url(calendar_id),
+            event_id
+        ))
+        .bearer_auth(access_token)
+        .json(times);
+
+    if let Some(etag) = etag {
+        request = request.header(reqwest::header::IF_MATCH, etag);
+    }
+
+    let res = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to contact Google: {}", e))?;
+
+    if res.status() == reqwest::StatusCode::PRECONDITION_FAILED {
+        return Ok(None);
+    }
+
+    parse_event_response(res).await.map(Some)
+}
+
+// Replaces the RRULE and EXDATE lines of a recurring event
+pub async fn patch_calendar_event_recurrence(
+    client: &reqwest::Client,
+    access_token: &str,
+    calendar_id: &str,
+    event_id: &str,
+    recurrence: &[String],
+) -> Result<GoogleEventItem, String> {
+    let res = client
+        .patch(format!(
+            "{}/{}?sendUpdates=all",
+            events_url(calendar_id),
+            event_id
+        ))
+        .bearer_auth(access_token)
+        .json(&GoogleEventRecurrencePatch {
+            recurrence: recurrence.to_vec(),
+        })
+        .send()
+        .await
+        .map_err(|e| format!("Failed to contact Google: {}", e))?;
+
+    parse_event_response(res).await
+}
+
+// Whether the event still sits exactly at the given times
+pub fn event_spans(event: &GoogleEventItem, start: OffsetDateTime, end: OffsetDateTime) -> bool {
+    let parse = |time: Option<&GoogleEventItemTime>| {
+        time.and_then(|time| time.date_time.as_deref())
+            .and_then(|date_time| OffsetDateTime::parse(date_time, &Rfc3339).ok())
+    };
+
+    parse(event.start.as_ref()) == Some(start) && parse(event.end.as_ref()) == Some(end)
+}
+
+pub async fn delete_calendar_event(
+    client: &reqwest::Client,
+    access_token: &str,
+    calendar_id: &str,
+    event_id: &str,
+) -> Result<(), String> {
+    let res = client
+        .delete(format!(
+            "{}/{}?sendUpdates=all",
+            events_url(calendar_id),
+            event_id
+        ))
+        .bearer_auth(access_token)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to contact Google: {}", e))?;
+
+    // 404 / 410 means the owner already removed it from their calendar
+    match res.status().as_u16() {
+        200..=299 | 404 | 410 => Ok(()),
+
+        _ => {
+            let err_text = res.text().await.unwrap_or_default();
+            Err(format!("Google Calendar API Error: {}", err_text))
+        }
+    }
+}
+
+pub async fn fetch_busy_periods(
+    client: &reqwest::Client,
+    access_token: &str,
+    calendar_id: &str,
+    time_min: OffsetDateTime,
+    time_max: OffsetDateTime,
+) -> Result<Vec<(OffsetDateTime, OffsetDateTime)>, String> {
+    let freebusy_request = FreeBusyRequest {
+        time_min: time_min.format(&Rfc3339).map_err(|e| e.to_string())?,
+        time_max: time_max.format(&Rfc3339).map_err(|e| e.to_string())?,
+        items: vec![FreeBusyRequestItem {
+            id: calendar_id.to_string(),
+        }],
+    };
+
+    let res = client
+        .post("https://www.googleapis.com/calendar/v3/freeBusy")
+        .bearer_auth(access_token)
+        .json(&freebusy_request)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to contact Google: {}", e))?;
+
+    if !res.status().is_success() {
+        let err_text = res.text().await.unwrap_or_default();
+        return Err(format!("Google Calendar API Error: {}", err_text));
+    }
+
+    let parsed = res
+        .json::<FreeBusyResponse>()
+        .await
+        .map_err(|e| format!("Failed to parse free/busy response: {}", e))?;
+
+    let mut busy_periods = Vec::new();
+
+    if let Some(calendar) = parsed.calendars.get(calendar_id) {
+        for busy_slot in &calendar.busy {
+            let start = OffsetDateTime::parse(&busy_slot.start, &Rfc3339).ok();
+            let end = OffsetDateTime::parse(&busy_slot.end, &Rfc3339).ok();
+
+            if let (Some(s), Some(e)) = (start, end) {
+                busy_periods.push((s, e));
+            }
+        }
+    }
+
+    Ok(busy_periods)
+}
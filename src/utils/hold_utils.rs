@@ -0,0 +1,115 @@
+use crate::structs::util_struct::SlotHold;
+use deadpool_redis::redis;
+use std::collections::HashMap;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+// How long a slot stays set aside for a checkout
+pub const HOLD_TTL_MINUTES: i64 = 5;
+
+// Checkouts a business may have in progress at once
+pub const MAX_HOLDS_PER_BUSINESS: usize = 50;
+
+// All holds of a business live in one hash keyed by hold token, so reading
+// them is a single command. Each hold knows when it expires, the hash itself
+// lasts as long as its newest hold.
+fn holds_key(business_id: Uuid) -> String {
+    format!("holds:{}", business_id)
+}
+
+fn parse_live_hold(json: &str, now: OffsetDateTime) -> Option<SlotHold> {
+    serde_json::from_str::<SlotHold>(json)
+        .ok()
+        .filter(|hold| hold.expires_at > now)
+}
+
+// Expired holds still in the hash are swept out along the way
+pub async fn save_hold(redis_pool: &deadpool_redis::Pool, hold: &SlotHold) -> Result<(), String> {
+    let mut conn = redis_pool.get().await.map_err(|e| e.to_string())?;
+    let key = holds_key(hold.business_id);
+    let json = serde_json::to_string(hold).map_err(|e| e.to_string())?;
+
+    let stored: HashMap<String, String> = redis::cmd("HGETALL")
+        .arg(&key)
+        .query_async(&mut conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let now = OffsetDateTime::now_utc();
+    let expired: Vec<&String> = stored
+        .iter()
+        .filter(|(_, json)| parse_live_hold(json, now).is_none())
+        .map(|(hold_token, _)| hold_token)
+        .collect();
+
+    let mut pipe = redis::pipe();
+    pipe.atomic();
+
+    if !expired.is_empty() {
+        pipe.cmd("HDEL").arg(&key).arg(&expired).ignore();
+    }
+
+    pipe.cmd("HSET")
+        .arg(&key)
+        .arg(hold.hold_token.to_string())
+        .arg(json)
+        .ignore()
+        .cmd("EXPIRE")
+        .arg(&key)
+        .arg(HOLD_TTL_MINUTES * 60)
+        .ignore()
+        .query_async(&mut conn)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// `None` once the hold has expired or was redeemed
+pub async fn find_hold(
+    redis_pool: &deadpool_redis::Pool,
+    business_id: Uuid,
+    hold_token: Uuid,
+) -> Result<Option<SlotHold>, String> {
+    let mut conn = redis_pool.get().await.map_err(|e| e.to_string())?;
+
+    let json: Option<String> = redis::cmd("HGET")
+        .arg(holds_key(business_id))
+        .arg(hold_token.to_string())
+        .query_async(&mut conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(json.and_then(|json| parse_live_hold(&json, OffsetDateTime::now_utc())))
+}
+
+// Every live hold of the business, whatever the service or staff member
+pub async fn business_holds(
+    redis_pool: &deadpool_redis::Pool,
+    business_id: Uuid,
+) -> Result<Vec<SlotHold>, String> {
+    let mut conn = redis_pool.get().await.map_err(|e| e.to_string())?;
+
+    let stored: Vec<String> = redis::cmd("HVALS")
+        .arg(holds_key(business_id))
+        .query_async(&mut conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let now = OffsetDateTime::now_utc();
+
+    Ok(stored
+        .iter()
+        .filter_map(|json| parse_live_hold(json, now))
+        .collect())
+}
+
+// A failed delete only keeps the slot blocked until the hold expires
+pub async fn release_hold(redis_pool: &deadpool_redis::Pool, business_id: Uuid, hold_token: Uuid) {
+    if let Ok(mut conn) = redis_pool.get().await {
+        let _: () = redis::cmd("HDEL")
+            .arg(holds_key(business_id))
+            .arg(hold_token.to_string())
+            .query_async(&mut conn)
+            .await
+            .unwrap_or(());
+    }
+}
@@ -0,0 +1,125 @@
+use crate::structs::util_struct::IdempotencyRecord;
+use deadpool_redis::redis;
+use serde_json::Value;
+use uuid::Uuid;
+
+// How long a replayed request still gets the original response
+pub const IDEMPOTENCY_TTL_HOURS: i64 = 24;
+
+// How long a claimed key stays taken without a stored response, enough for
+// one booking to finish but short enough that a crashed request frees it soon
+pub const PENDING_IDEMPOTENCY_TTL_SECONDS: i64 = 5 * 60;
+
+// The longest Idempotency-Key accepted, UUIDs and the like fit easily
+pub const MAX_IDEMPOTENCY_KEY_LENGTH: usize = 255;
+
+pub enum IdempotencyOutcome {
+    Replay(Value), // The response of the first request
+    InProgress,
+    PayloadMismatch,
+}
+
+// Keys are per business, two businesses' clients may pick the same one
+fn record_key(business_id: Uuid, idempotency_key: &str) -> String {
+    format!(
+        "idempotency:appointments:{}:{}",
+        business_id, idempotency_key
+    )
+}
+
+// What a request finding a record for its key gets instead of running again
+pub fn idempotency_outcome(record: IdempotencyRecord, payload: &Value) -> IdempotencyOutcome {
+    if record.payload != *payload {
+        return IdempotencyOutcome::PayloadMismatch;
+    }
+
+    match record.response {
+        Some(response) => IdempotencyOutcome::Replay(response),
+        None => IdempotencyOutcome::InProgress,
+    }
+}
+
+// Takes the key for this request, or hands back the record of whoever took
+// it first. Claiming is atomic, two retries racing each other cannot both run.
+pub async fn claim_idempotency_key(
+    redis_pool: &deadpool_redis::Pool,
+    business_id: Uuid,
+    idempotency_key: &str,
+    payload: &Value,
+) -> Result<Option<IdempotencyRecord>, String> {
+    let mut conn = redis_pool.get().await.map_err(|e| e.to_string())?;
+    let key = record_key(business_id, idempotency_key);
+
+    let pending = serde_json::to_string(&IdempotencyRecord {
+        payload: payload.clone(),
+        response: None,
+    })
+    .map_err(|e| e.to_string())?;
+
+    // The record may expire between the two commands, then the key is free again
+    for _ in 0..2 {
+        let claimed: Option<String> = redis::cmd("SET")
+            .arg(&key)
+            .arg(&pending)
+            .arg("NX")
+            .arg("EX")
+            .arg(PENDING_IDEMPOTENCY_TTL_SECONDS)
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if claimed.is_some() {
+            return Ok(None);
+        }
+
+        let existing: Option<String> = redis::cmd("GET")
+            .arg(&key)
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if let Some(existing) = existing {
+            return serde_json::from_str(&existing)
+                .map(Some)
+                .map_err(|e| e.to_string());
+        }
+    }
+
+    Err("Failed to claim the idempotency key.".to_string())
+}
+
+// Keeps the response for replays, the key stays taken for the full period
+pub async fn store_idempotent_response(
+    redis_pool: &deadpool_redis::Pool,
+    business_id: Uuid,
+    idempotency_key: &str,
+    record: &IdempotencyRecord,
+) -> Result<(), String> {
+    let mut conn = redis_pool.get().await.map_err(|e| e.to_string())?;
+    let json = serde_json::to_string(record).map_err(|e| e.to_string())?;
+
+    redis::cmd("SET")
+        .arg(record_key(business_id, idempotency_key))
+        .arg(json)
+        .arg("EX")
+        .arg(IDEMPOTENCY_TTL_HOURS * 60 * 60)
+        .query_async(&mut conn)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// A request that failed is not replayed, the client may simply try again.
+// Should the delete fail, the key stays taken until the pending record expires.
+pub async fn release_idempotency_key(
+    redis_pool: &deadpool_redis::Pool,
+    business_id: Uuid,
+    idempotency_key: &str,
+) {
+    if let Ok(mut conn) = redis_pool.get().await {
+        let _: () = redis::cmd("DEL")
+            .arg(record_key(business_id, idempotency_key))
+            .query_async(&mut conn)
+            .await
+            .unwrap_or(());
+    }
+}
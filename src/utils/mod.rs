@@ -1,4 +1,11 @@
 pub mod api_doc;
 pub mod auth_utils;
+pub mod availability_utils;
+pub mod cache_utils;
+pub mod calendar_utils;
+pub mod hold_utils;
+pub mod idempotency_utils;
+pub mod notifier_utils;
 pub mod others_utils;
 pub mod response_utils;
+pub mod waitlist_utils;
@@ -0,0 +1,74 @@
+use crate::{config::Config, structs::util_struct::WaitlistNotification};
+use actix_web::web;
+use std::{fs::OpenOptions, future::Future, io::Write, pin::Pin, sync::Arc};
+
+pub type NotifyFuture<'a> = Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>>;
+
+// Passes an opening on to a waitlisted customer. Email or SMS providers plug
+// in here, the waitlist itself decides who hears about what.
+pub trait Notifier: Send + Sync {
+    fn notify<'a>(&'a self, notification: &'a WaitlistNotification) -> NotifyFuture<'a>;
+}
+
+// Prints each notification, for local development
+pub struct LogNotifier;
+
+impl Notifier for LogNotifier {
+    fn notify<'a>(&'a self, notification: &'a WaitlistNotification) -> NotifyFuture<'a> {
+        Box::pin(async move {
+            println!(
+                "Waitlist: {} <{}> can book {} at {}",
+                notification.customer_name,
+                notification
+                    .customer_email
+                    .as_deref()
+                    .or(notification.customer_phone.as_deref())
+                    .unwrap_or("N/A"),
+                notification.service_name,
+                notification.slot_start_time
+            );
+
+            Ok(())
+        })
+    }
+}
+
+// Appends each notification to a file as one line of JSON
+pub struct FileNotifier {
+    path: String,
+}
+
+impl FileNotifier {
+    pub fn new(path: String) -> Self {
+        Self { path }
+    }
+}
+
+impl Notifier for FileNotifier {
+    fn notify<'a>(&'a self, notification: &'a WaitlistNotification) -> NotifyFuture<'a> {
+        Box::pin(async move {
+            let line = serde_json::to_string(notification).map_err(|e| e.to_string())?;
+            let path = self.path.clone();
+
+            // File writes block, they run off the request workers
+            web::block(move || {
+                let mut file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&path)
+                    .map_err(|e| format!("Failed to open {}: {}", path, e))?;
+
+                writeln!(file, "{}", line).map_err(|e| e.to_string())
+            })
+            .await
+            .map_err(|e| e.to_string())?
+        })
+    }
+}
+
+pub fn build_notifier(config: &Config) -> Arc<dyn Notifier> {
+    match config.notifier.as_str() {
+        "file" => Arc::new(FileNotifier::new(config.notifier_file_path.clone())),
+        _ => Arc::new(LogNotifier),
+    }
+}
@@ -1,11 +1,19 @@
-use crate::structs::util_struct::TimeSlot;
-use chrono::{Datelike, TimeZone, Timelike};
+use crate::structs::{
+    db_struct::{
+        AvailabilityOverride, AvailabilityRule, BusinessBreak, DayTimeSlot, WaitlistEntry,
+    },
+    util_struct::{
+        AvailabilityRuleError, GroupSession, OpeningSchedule, SlotHold, SlotSettings, TimeSlot,
+    },
+};
+use chrono::{Datelike, Offset, TimeZone, Timelike};
 use chrono_tz::Tz;
-use std::str::FromStr;
+use std::{collections::HashSet, net::IpAddr, str::FromStr};
 use time::{
     Date, Duration, Month, OffsetDateTime, PrimitiveDateTime, Time,
     format_description::well_known::Rfc3339,
 };
+use uuid::Uuid;
 
 pub fn convert_to_local_primitive(
     dt_utc: OffsetDateTime,
@@ -41,7 +49,20 @@ pub fn convert_to_local_primitive(
     Ok(PrimitiveDateTime::new(time_date, time_time))
 }
 
-pub fn local_to_utc(local_date: PrimitiveDateTime, tz: &Tz) -> Option<time::OffsetDateTime> {
+// How a wall-clock time repeated by a DST fall-back is mapped to an instant
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AmbiguousTime {
+    Earliest,
+    Latest,
+}
+
+// DST policy: ambiguous times take the requested occurrence, and times
+// skipped by a spring-forward are shifted forward by the size of the gap
+pub fn resolve_local_time(
+    local_date: PrimitiveDateTime,
+    tz: &Tz,
+    ambiguous: AmbiguousTime,
+) -> Option<OffsetDateTime> {
     // Convert time::PrimitiveDateTime -> chrono::NaiveDateTime
     let chrono_naive = chrono::NaiveDate::from_ymd_opt(
         local_date.year(),
@@ -55,49 +76,1035 @@ pub fn local_to_utc(local_date: PrimitiveDateTime, tz: &Tz) -> Option<time::Offs
     )?;
 
     // Attach Timezone
-    let chrono_tz_aware = tz.from_local_datetime(&chrono_naive).single()?;
+    let timestamp = match tz.from_local_datetime(&chrono_naive) {
+        chrono::LocalResult::Single(t) => t.timestamp(),
 
-    // Convert to UTC
-    let chrono_utc = chrono_tz_aware.with_timezone(&chrono::Utc);
+        chrono::LocalResult::Ambiguous(earliest, latest) => match ambiguous {
+            AmbiguousTime::Earliest => earliest.timestamp(),
+            AmbiguousTime::Latest => latest.timestamp(),
+        },
+
+        // Read the time with the offset from before the gap, which lands
+        // the same distance past the transition
+        chrono::LocalResult::None => {
+            let before_gap = chrono_naive - chrono::Duration::days(1);
+            let offset = tz.offset_from_utc_datetime(&before_gap).fix();
+
+            (chrono_naive - chrono::Duration::seconds(offset.local_minus_utc() as i64))
+                .and_utc()
+                .timestamp()
+        }
+    };
 
     // Convert back to time::OffsetDateTime
-    time::OffsetDateTime::from_unix_timestamp(chrono_utc.timestamp()).ok()
+    OffsetDateTime::from_unix_timestamp(timestamp).ok()
+}
+
+// Single instants resolve to the earliest occurrence
+pub fn local_to_utc(local_date: PrimitiveDateTime, tz: &Tz) -> Option<OffsetDateTime> {
+    resolve_local_time(local_date, tz, AmbiguousTime::Earliest)
+}
+
+// A local opening period as real instants, stretched to its widest reading
+// when either end falls in a DST transition
+pub fn period_to_utc(
+    start_time: PrimitiveDateTime,
+    end_time: PrimitiveDateTime,
+    tz: &Tz,
+) -> Option<(OffsetDateTime, OffsetDateTime)> {
+    Some((
+        resolve_local_time(start_time, tz, AmbiguousTime::Earliest)?,
+        resolve_local_time(end_time, tz, AmbiguousTime::Latest)?,
+    ))
 }
 
 pub fn generate_slots(
     start_time: PrimitiveDateTime,
     end_time: PrimitiveDateTime,
-    duration_minutes: i64,
+    settings: &SlotSettings,
     time_zone: &chrono_tz::Tz,
     blocked_periods: &[(OffsetDateTime, OffsetDateTime)],
 ) -> Vec<TimeSlot> {
     let mut available_slots = Vec::new();
-    let step = Duration::minutes(30);
-    let mut current_open = start_time;
-
-    while current_open + Duration::minutes(duration_minutes) <= end_time {
-        let slot_end = current_open + Duration::minutes(duration_minutes);
-
-        // Convert to UTC for comparison
-        if let (Some(slot_start_utc), Some(slot_end_utc)) = (
-            local_to_utc(current_open, time_zone),
-            local_to_utc(slot_end, time_zone),
-        ) {
-            // The Collision Logic
-            let is_clashing = blocked_periods.iter().any(|(busy_start, busy_end)| {
-                // Overlap: StartA < EndB && EndA > StartB
-                *busy_start < slot_end_utc && *busy_end > slot_start_utc
+    // A zero step would never leave the loop
+    let step = Duration::minutes(settings.interval.max(1));
+    let duration = Duration::minutes(settings.duration);
+    let buffer_before = Duration::minutes(settings.buffer_before);
+    let buffer_after = Duration::minutes(settings.buffer_after);
+
+    // Walk real time rather than the wall clock, so a DST day offers exactly
+    // the hours it has and every slot lasts its full duration
+    let Some((open_utc, close_utc)) = period_to_utc(start_time, end_time, time_zone) else {
+        return available_slots;
+    };
+
+    let mut slot_start_utc = open_utc;
+
+    while slot_start_utc + duration <= close_utc {
+        let slot_end_utc = slot_start_utc + duration;
+
+        // The Collision Logic, buffers included
+        let is_clashing = blocked_periods.iter().any(|(busy_start, busy_end)| {
+            // Overlap: StartA < EndB && EndA > StartB
+            *busy_start < slot_end_utc + buffer_after && *busy_end > slot_start_utc - buffer_before
+        });
+
+        if !is_clashing {
+            available_slots.push(TimeSlot {
+                start_time: slot_start_utc.format(&Rfc3339).unwrap(),
+                end_time: slot_end_utc.format(&Rfc3339).unwrap(),
+                remaining_seats: settings.capacity,
             });
+        }
+
+        slot_start_utc += step;
+    }
+
+    available_slots
+}
+
+// Removes `cut` from `period`, leaving zero, one or two pieces
+pub fn subtract_period(
+    period: (OffsetDateTime, OffsetDateTime),
+    cut: (OffsetDateTime, OffsetDateTime),
+) -> Vec<(OffsetDateTime, OffsetDateTime)> {
+    let (start, end) = period;
+    let (cut_start, cut_end) = cut;
+
+    // No overlap, nothing to remove
+    if cut_start >= end || cut_end <= start {
+        return vec![period];
+    }
+
+    let mut pieces = Vec::new();
+
+    if start < cut_start {
+        pieces.push((start, cut_start));
+    }
+
+    if cut_end < end {
+        pieces.push((cut_end, end));
+    }
+
+    pieces
+}
 
-            if !is_clashing {
-                available_slots.push(TimeSlot {
-                    start_time: slot_start_utc.format(&Rfc3339).unwrap(),
-                    end_time: slot_end_utc.format(&Rfc3339).unwrap(),
-                });
+// Removes every one of `cuts` from `period`
+pub fn subtract_periods(
+    period: (OffsetDateTime, OffsetDateTime),
+    cuts: &[(OffsetDateTime, OffsetDateTime)],
+) -> Vec<(OffsetDateTime, OffsetDateTime)> {
+    cuts.iter().fold(vec![period], |pieces, cut| {
+        pieces
+            .into_iter()
+            .flat_map(|piece| subtract_period(piece, *cut))
+            .collect()
+    })
+}
+
+// Earliest and latest start times a customer may book from `now`
+pub fn booking_window(
+    now: OffsetDateTime,
+    min_notice_minutes: i64,
+    max_advance_days: i64,
+) -> (OffsetDateTime, OffsetDateTime) {
+    (
+        now + Duration::minutes(min_notice_minutes),
+        now + Duration::days(max_advance_days),
+    )
+}
+
+// Drops slots starting outside the bookable window, used on cached slots too
+pub fn retain_bookable_slots(
+    slots: &mut Vec<TimeSlot>,
+    earliest: OffsetDateTime,
+    latest: OffsetDateTime,
+) {
+    slots.retain(|slot| {
+        OffsetDateTime::parse(&slot.start_time, &Rfc3339)
+            .is_ok_and(|start| start >= earliest && start <= latest)
+    });
+}
+
+// Date overrides replace the weekly rules for their date, a closed override
+// wins over any windows set alongside it
+pub fn effective_opening_hours(
+    overrides: &[AvailabilityOverride],
+    weekly_rules: &[AvailabilityRule],
+) -> Vec<(Time, Time)> {
+    if overrides.is_empty() {
+        return weekly_rules
+            .iter()
+            .map(|rule| (rule.open_time, rule.close_time))
+            .collect();
+    }
+
+    let mut windows = Vec::new();
+
+    for item in overrides {
+        match (item.open_time, item.close_time) {
+            (Some(open), Some(close)) => windows.push((open, close)),
+            _ => return Vec::new(),
+        }
+    }
+
+    windows
+}
+
+// A window closing at or before its open time runs into the next day
+pub fn opening_period(
+    date: Date,
+    open_time: Time,
+    close_time: Time,
+) -> (PrimitiveDateTime, PrimitiveDateTime) {
+    let open = PrimitiveDateTime::new(date, open_time);
+    let close = PrimitiveDateTime::new(date, close_time);
+
+    if close_time <= open_time {
+        (open, close + Duration::days(1))
+    } else {
+        (open, close)
+    }
+}
+
+// Slots starting on `date`, from its own windows and from the overnight
+// windows of the day before that spill into it
+pub fn generate_day_slots(
+    date: Date,
+    opening_hours: &[(Time, Time)],
+    previous_day_hours: &[(Time, Time)],
+    settings: &SlotSettings,
+    time_zone: &chrono_tz::Tz,
+    blocked_periods: &[(OffsetDateTime, OffsetDateTime)],
+) -> Vec<TimeSlot> {
+    let mut periods: Vec<(PrimitiveDateTime, PrimitiveDateTime)> = Vec::new();
+
+    for (open_time, close_time) in opening_hours {
+        periods.push(opening_period(date, *open_time, *close_time));
+    }
+
+    if let Some(previous_day) = date.previous_day() {
+        for (open_time, close_time) in previous_day_hours {
+            if close_time <= open_time {
+                periods.push(opening_period(previous_day, *open_time, *close_time));
             }
         }
-        current_open += step;
     }
 
+    let mut available_slots = Vec::new();
+
+    for (start, end) in periods {
+        available_slots.append(&mut generate_slots(
+            start,
+            end,
+            settings,
+            time_zone,
+            blocked_periods,
+        ));
+    }
+
+    // Slots starting on another day belong to that day
+    let day_start = local_to_utc(PrimitiveDateTime::new(date, Time::MIDNIGHT), time_zone);
+    let day_end = date
+        .next_day()
+        .and_then(|next| local_to_utc(PrimitiveDateTime::new(next, Time::MIDNIGHT), time_zone));
+
+    if let (Some(day_start), Some(day_end)) = (day_start, day_end) {
+        available_slots.retain(|slot| {
+            OffsetDateTime::parse(&slot.start_time, &Rfc3339)
+                .is_ok_and(|start| start >= day_start && start < day_end)
+        });
+    }
+
+    available_slots.sort_by(|a, b| a.start_time.cmp(&b.start_time));
     available_slots
 }
+
+// Whether a booking fits one opening window of its local start date, or an
+// overnight window of the day before, compared as real instants so DST
+// transitions inside a window are accounted for
+pub fn fits_opening_hours(
+    start_time: OffsetDateTime,
+    end_time: OffsetDateTime,
+    local_date: Date,
+    opening_hours: &[(Time, Time)],
+    previous_day_hours: &[(Time, Time)],
+    time_zone: &Tz,
+) -> bool {
+    let fits = |(open, close): (PrimitiveDateTime, PrimitiveDateTime)| {
+        period_to_utc(open, close, time_zone)
+            .is_some_and(|(open_utc, close_utc)| start_time >= open_utc && end_time <= close_utc)
+    };
+
+    let fits_today = opening_hours
+        .iter()
+        .any(|(open_time, close_time)| fits(opening_period(local_date, *open_time, *close_time)));
+
+    let fits_spill_over = local_date.previous_day().is_some_and(|previous_day| {
+        previous_day_hours
+            .iter()
+            .filter(|(open_time, close_time)| close_time <= open_time)
+            .any(|(open_time, close_time)| {
+                fits(opening_period(previous_day, *open_time, *close_time))
+            })
+    });
+
+    fits_today || fits_spill_over
+}
+
+const SECONDS_PER_DAY: i64 = 86_400;
+const SECONDS_PER_WEEK: i64 = 7 * SECONDS_PER_DAY;
+
+// A weekly window as seconds from Monday 00:00, overnight ones run past the day
+struct WeekWindow {
+    day_of_week: i32,
+    start: i64,
+    end: i64,
+    indexes: Vec<usize>,
+}
+
+// Checks every submitted rule and merges overlapping windows of the same day.
+// Returns the rules to store, or one error per offending rule
+pub fn validate_weekly_rules(
+    rules: &[DayTimeSlot],
+) -> Result<Vec<(i32, Time, Time)>, Vec<AvailabilityRuleError>> {
+    let rule_error = |index: usize, field: &str, message: String| AvailabilityRuleError {
+        index,
+        field: field.to_string(),
+        message,
+    };
+
+    let mut errors = Vec::new();
+    let mut windows: Vec<WeekWindow> = Vec::new();
+
+    let time_format = time::macros::format_description!("[hour]:[minute]:[second]");
+
+    for (index, rule) in rules.iter().enumerate() {
+        if !(1..=7).contains(&rule.day_of_week) {
+            errors.push(rule_error(
+                index,
+                "day_of_week",
+                format!(
+                    "day_of_week must be between 1 (Monday) and 7 (Sunday), got {}.",
+                    rule.day_of_week
+                ),
+            ));
+        }
+
+        let open_time = Time::parse(&rule.open_time, &time_format);
+        let close_time = Time::parse(&rule.close_time, &time_format);
+
+        if open_time.is_err() {
+            errors.push(rule_error(
+                index,
+                "open_time",
+                format!("Invalid open_time {}. Expected HH:MM:SS.", rule.open_time),
+            ));
+        }
+
+        if close_time.is_err() {
+            errors.push(rule_error(
+                index,
+                "close_time",
+                format!("Invalid close_time {}. Expected HH:MM:SS.", rule.close_time),
+            ));
+        }
+
+        let (Ok(open_time), Ok(close_time)) = (open_time, close_time) else {
+            continue;
+        };
+
+        // An earlier close_time means the shift runs past midnight
+        if close_time == open_time {
+            errors.push(rule_error(
+                index,
+                "close_time",
+                format!(
+                    "open_time and close_time cannot both be {}.",
+                    rule.open_time
+                ),
+            ));
+            continue;
+        }
+
+        let open = seconds_of_day(open_time);
+        let mut close = seconds_of_day(close_time);
+
+        if close < open {
+            close += SECONDS_PER_DAY;
+        }
+
+        let day_start = (rule.day_of_week as i64 - 1) * SECONDS_PER_DAY;
+
+        windows.push(WeekWindow {
+            day_of_week: rule.day_of_week,
+            start: day_start + open,
+            end: day_start + close,
+            indexes: vec![index],
+        });
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    // Merge overlapping or touching windows that belong to the same day
+    windows.sort_by_key(|w| (w.day_of_week, w.start));
+
+    let mut merged: Vec<WeekWindow> = Vec::new();
+
+    for window in windows {
+        match merged.last_mut() {
+            Some(last) if last.day_of_week == window.day_of_week && window.start <= last.end => {
+                last.end = last.end.max(window.end);
+                last.indexes.extend(window.indexes);
+            }
+            _ => merged.push(window),
+        }
+    }
+
+    for window in &merged {
+        if window.end - window.start >= SECONDS_PER_DAY {
+            errors.push(rule_error(
+                window.indexes[window.indexes.len() - 1],
+                "close_time",
+                "Windows on this day add up to 24 hours or more.".to_string(),
+            ));
+        }
+    }
+
+    // An overnight window may still run into the next day's hours, Sunday
+    // night wraps around to Monday
+    for (i, earlier) in merged.iter().enumerate() {
+        for later in merged.iter().skip(i + 1) {
+            let overlaps = |start: i64, end: i64| start < earlier.end && end > earlier.start;
+
+            let clashes = overlaps(later.start, later.end)
+                || overlaps(later.start - SECONDS_PER_WEEK, later.end - SECONDS_PER_WEEK);
+
+            if clashes && earlier.day_of_week != later.day_of_week {
+                errors.push(rule_error(
+                    later.indexes[0],
+                    "open_time",
+                    format!(
+                        "Overlaps the overnight hours of rule {}.",
+                        earlier.indexes[0]
+                    ),
+                ));
+            }
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    Ok(merged
+        .into_iter()
+        .map(|window| {
+            let day_start = (window.day_of_week as i64 - 1) * SECONDS_PER_DAY;
+
+            (
+                window.day_of_week,
+                time_of_day(window.start - day_start),
+                time_of_day((window.end - day_start) % SECONDS_PER_DAY),
+            )
+        })
+        .collect())
+}
+
+fn seconds_of_day(t: Time) -> i64 {
+    t.hour() as i64 * 3600 + t.minute() as i64 * 60 + t.second() as i64
+}
+
+fn time_of_day(seconds: i64) -> Time {
+    Time::from_hms(
+        (seconds / 3600) as u8,
+        ((seconds % 3600) / 60) as u8,
+        (seconds % 60) as u8,
+    )
+    .unwrap_or(Time::MIDNIGHT)
+}
+
+// Real instants of the recurring breaks falling on any date from `from` to `to`
+pub fn break_periods(
+    breaks: &[BusinessBreak],
+    from: Date,
+    to: Date,
+    time_zone: &Tz,
+) -> Vec<(OffsetDateTime, OffsetDateTime)> {
+    let mut periods = Vec::new();
+    let mut date = from;
+
+    while date <= to {
+        let weekday = date.weekday().number_from_monday() as i32;
+
+        for item in breaks.iter().filter(|b| b.day_of_week == weekday) {
+            if let Some(period) = period_to_utc(
+                PrimitiveDateTime::new(date, item.start_time),
+                PrimitiveDateTime::new(date, item.end_time),
+                time_zone,
+            ) {
+                periods.push(period);
+            }
+        }
+
+        date = match date.next_day() {
+            Some(d) => d,
+            None => break,
+        };
+    }
+
+    periods
+}
+
+pub type OpeningWindow = (Time, Time);
+
+// Business hours of the date and the day before, narrowed to when the
+// service is offered and the staff member works if either has weekly rules
+pub fn schedule_hours(
+    schedule: &OpeningSchedule,
+    staff_id: Option<Uuid>,
+    date: Date,
+) -> (Vec<OpeningWindow>, Vec<OpeningWindow>) {
+    let business_hours = |day: Option<Date>| -> Vec<OpeningWindow> {
+        let Some(day) = day else {
+            return Vec::new();
+        };
+
+        let day_of_week = day.weekday().number_from_monday() as i32;
+
+        effective_opening_hours(
+            schedule.overrides.get(&day).map_or(&[], Vec::as_slice),
+            schedule
+                .weekly_rules
+                .get(&day_of_week)
+                .map_or(&[], Vec::as_slice),
+        )
+    };
+
+    let mut hours = (
+        business_hours(Some(date)),
+        business_hours(date.previous_day()),
+    );
+
+    hours = narrow_to_weekly_rules(hours, &schedule.service_rules, date);
+
+    if let Some(staff_rules) = staff_id.and_then(|id| schedule.staff_rules.get(&id)) {
+        hours = narrow_to_weekly_rules(hours, staff_rules, date);
+    }
+
+    hours
+}
+
+// Weekly rules only ever take time away, none at all leave the hours as they are
+fn narrow_to_weekly_rules(
+    (opening_hours, previous_day_hours): (Vec<OpeningWindow>, Vec<OpeningWindow>),
+    rules: &[(i32, Time, Time)],
+    date: Date,
+) -> (Vec<OpeningWindow>, Vec<OpeningWindow>) {
+    if rules.is_empty() {
+        return (opening_hours, previous_day_hours);
+    }
+
+    let windows_on = |day: Option<Date>| -> Vec<OpeningWindow> {
+        let Some(day) = day else {
+            return Vec::new();
+        };
+
+        let day_of_week = day.weekday().number_from_monday() as i32;
+
+        rules
+            .iter()
+            .filter(|(rule_day, _, _)| *rule_day == day_of_week)
+            .map(|(_, open_time, close_time)| (*open_time, *close_time))
+            .collect()
+    };
+
+    intersect_opening_hours(
+        &opening_hours,
+        &previous_day_hours,
+        &windows_on(Some(date)),
+        &windows_on(date.previous_day()),
+    )
+}
+
+// Narrows the business hours of a date to when the service is offered. Both
+// sides are laid out on one timeline from the previous day's midnight, so an
+// overnight window on either side still meets its counterpart.
+pub fn intersect_opening_hours(
+    business_hours: &[OpeningWindow],
+    business_previous_day_hours: &[OpeningWindow],
+    service_hours: &[OpeningWindow],
+    service_previous_day_hours: &[OpeningWindow],
+) -> (Vec<OpeningWindow>, Vec<OpeningWindow>) {
+    let spans = |today: &[OpeningWindow], previous_day: &[OpeningWindow]| {
+        previous_day
+            .iter()
+            .map(|window| (-SECONDS_PER_DAY, window))
+            .chain(today.iter().map(|window| (0, window)))
+            .map(|(offset, (open_time, close_time))| {
+                let start = offset + seconds_of_day(*open_time);
+                let mut end = offset + seconds_of_day(*close_time);
+
+                if close_time <= open_time {
+                    end += SECONDS_PER_DAY;
+                }
+
+                (start, end)
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let business = spans(business_hours, business_previous_day_hours);
+    let service = spans(service_hours, service_previous_day_hours);
+
+    let mut opening_hours = Vec::new();
+    let mut previous_day_hours = Vec::new();
+
+    for (business_start, business_end) in &business {
+        for (service_start, service_end) in &service {
+            let start = *business_start.max(service_start);
+            let end = *business_end.min(service_end);
+
+            if start >= end {
+                continue;
+            }
+
+            let window = (
+                time_of_day(start.rem_euclid(SECONDS_PER_DAY)),
+                time_of_day(end.rem_euclid(SECONDS_PER_DAY)),
+            );
+
+            if start < 0 {
+                previous_day_hours.push(window);
+            } else {
+                opening_hours.push(window);
+            }
+        }
+    }
+
+    opening_hours.sort();
+    previous_day_hours.sort();
+
+    (opening_hours, previous_day_hours)
+}
+
+// Staff share one list of openings, a start offered by several shows once
+// with the seats of all of them
+pub fn merge_lane_slots(slots: &mut Vec<TimeSlot>) {
+    slots.sort_by(|a, b| a.start_time.cmp(&b.start_time));
+    slots.dedup_by(|later, kept| {
+        if later.start_time != kept.start_time {
+            return false;
+        }
+
+        kept.remaining_seats += later.remaining_seats;
+        true
+    });
+}
+
+// A slot lining up with a session of the same service offers the seats left
+// in it, any other slot overlapping a session would clash with the class.
+// Joining a session takes no resource the session does not hold already, so
+// fully reserved periods only stop the other slots.
+pub fn apply_group_sessions(
+    slots: &mut Vec<TimeSlot>,
+    sessions: &[GroupSession],
+    reserved_periods: &[(OffsetDateTime, OffsetDateTime)],
+    service_id: Uuid,
+    settings: &SlotSettings,
+) {
+    let buffer_before = Duration::minutes(settings.buffer_before);
+    let buffer_after = Duration::minutes(settings.buffer_after);
+
+    slots.retain_mut(|slot| {
+        let (Ok(start), Ok(end)) = (
+            OffsetDateTime::parse(&slot.start_time, &Rfc3339),
+            OffsetDateTime::parse(&slot.end_time, &Rfc3339),
+        ) else {
+            return false;
+        };
+
+        let clashes = |(blocked_start, blocked_end): (OffsetDateTime, OffsetDateTime)| {
+            blocked_start < end + buffer_after && blocked_end > start - buffer_before
+        };
+
+        let mut joins_session = false;
+
+        for session in sessions {
+            if session.service_id == service_id
+                && session.start_time == start
+                && session.end_time == end
+            {
+                slot.remaining_seats -= session.booked_seats;
+                joins_session = true;
+            } else if clashes(session.blocked_period) {
+                return false;
+            }
+        }
+
+        if !joins_session && reserved_periods.iter().any(|period| clashes(*period)) {
+            return false;
+        }
+
+        slot.remaining_seats > 0
+    });
+}
+
+// Lanes with a class already running at the time fill up before new sessions
+// are opened, otherwise the lanes keep their order
+pub fn order_lane_candidates<T>(candidates: &mut [(T, Option<Uuid>)]) {
+    candidates.sort_by_key(|(_, session_id)| session_id.is_none());
+}
+
+// Bookings without a staff member predate the business having staff, they
+// keep every lane busy. Any other booking only blocks its own lane.
+pub fn lanes_collide(lane: Option<Uuid>, other: Option<Uuid>) -> bool {
+    lane.is_none() || other.is_none() || lane == other
+}
+
+// The holds of one lane as one-seat sessions, so slot listings block them like
+// bookings: a hold of the same class leaves its other seats open
+pub fn hold_sessions(holds: &[SlotHold], staff_id: Option<Uuid>) -> Vec<GroupSession> {
+    holds
+        .iter()
+        .filter(|hold| hold.staff_id == staff_id)
+        .map(|hold| GroupSession {
+            service_id: hold.service_id,
+            start_time: hold.start_time,
+            end_time: hold.end_time,
+            blocked_period: (hold.blocked_start, hold.blocked_end),
+            booked_seats: 1,
+        })
+        .collect()
+}
+
+// Holds of other lanes that still keep this one busy, see `lanes_collide`
+pub fn cross_lane_hold_periods(
+    holds: &[SlotHold],
+    staff_id: Option<Uuid>,
+) -> Vec<(OffsetDateTime, OffsetDateTime)> {
+    holds
+        .iter()
+        .filter(|hold| hold.staff_id != staff_id && lanes_collide(hold.staff_id, staff_id))
+        .map(|hold| (hold.blocked_start, hold.blocked_end))
+        .collect()
+}
+
+// Seats of a lane's window promised to checkouts still in progress. A hold of
+// the same group class on the lane takes one seat, any other overlapping hold
+// that collides with the lane keeps the window to itself (`None`).
+pub fn held_seats(
+    holds: &[SlotHold],
+    staff_id: Option<Uuid>,
+    (service_id, capacity): (Uuid, i32),
+    (start_time, end_time): (OffsetDateTime, OffsetDateTime),
+    (blocked_start, blocked_end): (OffsetDateTime, OffsetDateTime),
+) -> Option<i32> {
+    let mut seats = 0;
+
+    let colliding = holds
+        .iter()
+        .filter(|hold| lanes_collide(hold.staff_id, staff_id));
+
+    for hold in colliding {
+        if capacity > 1
+            && hold.staff_id == staff_id
+            && hold.service_id == service_id
+            && hold.start_time == start_time
+            && hold.end_time == end_time
+        {
+            seats += 1;
+        } else if hold.blocked_start < blocked_end && hold.blocked_end > blocked_start {
+            return None;
+        }
+    }
+
+    Some(seats)
+}
+
+// The address a request came from. X-Forwarded-For is only believed when the
+// connection comes from a trusted proxy, and then read from the right, the
+// first entry no trusted proxy added is the client. Anything left of it is
+// whatever the client chose to send.
+pub fn client_ip(
+    peer_ip: Option<IpAddr>,
+    forwarded_for: Option<&str>,
+    trusted_proxies: &[IpAddr],
+) -> Option<String> {
+    let peer_ip = peer_ip?;
+
+    if !trusted_proxies.contains(&peer_ip) {
+        return Some(peer_ip.to_string());
+    }
+
+    let mut client = peer_ip;
+
+    for hop in forwarded_for.unwrap_or_default().rsplit(',') {
+        match hop.trim().parse::<IpAddr>() {
+            Ok(ip) => {
+                client = ip;
+
+                if !trusted_proxies.contains(&ip) {
+                    break;
+                }
+            }
+
+            // A hop that is no address cannot be vouched for, stop at the last good one
+            Err(_) => break,
+        }
+    }
+
+    Some(client.to_string())
+}
+
+// Holds are handed out without signing in, so each client keeps one checkout
+// going at a time and a business only so many altogether
+pub fn check_hold_limit(
+    holds: &[SlotHold],
+    client_ip: Option<&str>,
+    max_holds: usize,
+) -> Result<(), String> {
+    if client_ip.is_some()
+        && holds
+            .iter()
+            .any(|hold| hold.client_ip.as_deref() == client_ip)
+    {
+        return Err("You already hold a slot, book it or let it expire first.".to_string());
+    }
+
+    if holds.len() >= max_holds {
+        return Err("Too many checkouts are in progress, please try again shortly.".to_string());
+    }
+
+    Ok(())
+}
+
+// Stretches where `capacity` or more of the periods overlap, so a resource
+// shared by all of them has nothing left to give
+pub fn saturated_periods(
+    periods: &[(OffsetDateTime, OffsetDateTime)],
+    capacity: usize,
+) -> Vec<(OffsetDateTime, OffsetDateTime)> {
+    // Ends sort before starts at the same instant, touching periods do not overlap
+    let mut events: Vec<(OffsetDateTime, i32)> = periods
+        .iter()
+        .flat_map(|(start, end)| [(*start, 1), (*end, -1)])
+        .collect();
+    events.sort();
+
+    let mut saturated = Vec::new();
+    let mut in_use = 0;
+    let mut saturated_since = None;
+
+    for (instant, change) in events {
+        in_use += change;
+
+        match saturated_since {
+            None if in_use as usize >= capacity => saturated_since = Some(instant),
+
+            Some(since) if (in_use as usize) < capacity => {
+                // A booking ending as another starts leaves no real gap
+                match saturated.last_mut() {
+                    Some((_, end)) if *end == since => *end = instant,
+                    _ if since < instant => saturated.push((since, instant)),
+                    _ => {}
+                }
+                saturated_since = None;
+            }
+
+            _ => {}
+        }
+    }
+
+    saturated
+}
+
+// Local starts of a series, a fixed number of weeks apart so the wall-clock
+// time holds across DST changes. Stops after `count` occurrences or past the
+// `until` date, and one beyond `limit` so an overlong rule can be told apart.
+pub fn series_local_starts(
+    first: PrimitiveDateTime,
+    interval_weeks: i64,
+    count: Option<usize>,
+    until: Option<Date>,
+    limit: usize,
+) -> Vec<PrimitiveDateTime> {
+    let mut starts = Vec::new();
+    let mut next = first;
+
+    while starts.len() <= limit
+        && count.is_none_or(|count| starts.len() < count)
+        && until.is_none_or(|until| next.date() <= until)
+    {
+        starts.push(next);
+        next += Duration::weeks(interval_weeks);
+    }
+
+    starts
+}
+
+// RRULE and EXDATE lines of the recurring Google event of a series. The event
+// runs until the last booked occurrence, skipped ones become exceptions.
+pub fn series_recurrence(
+    interval_weeks: i64,
+    last_start: OffsetDateTime,
+    skipped_local_starts: &[PrimitiveDateTime],
+    time_zone: &str,
+) -> Vec<String> {
+    let utc_format =
+        time::macros::format_description!("[year][month][day]T[hour][minute][second]Z");
+    let local_format =
+        time::macros::format_description!("[year][month][day]T[hour][minute][second]");
+
+    let until = last_start
+        .to_offset(time::UtcOffset::UTC)
+        .format(&utc_format)
+        .unwrap_or_default();
+
+    let mut recurrence = vec![format!(
+        "RRULE:FREQ=WEEKLY;INTERVAL={};UNTIL={}",
+        interval_weeks, until
+    )];
+
+    let exceptions: Vec<String> = skipped_local_starts
+        .iter()
+        .filter_map(|start| start.format(&local_format).ok())
+        .collect();
+
+    if !exceptions.is_empty() {
+        recurrence.push(format!(
+            "EXDATE;TZID={}:{}",
+            time_zone,
+            exceptions.join(",")
+        ));
+    }
+
+    recurrence
+}
+
+// The recurrence of a series event cut short, so no instance starts at or
+// after `from`. Exceptions are kept, the ones past the end do no harm.
+pub fn end_recurrence_before(recurrence: &[String], from: OffsetDateTime) -> Vec<String> {
+    let utc_format =
+        time::macros::format_description!("[year][month][day]T[hour][minute][second]Z");
+
+    let until = (from - Duration::seconds(1))
+        .to_offset(time::UtcOffset::UTC)
+        .format(&utc_format)
+        .unwrap_or_default();
+
+    recurrence
+        .iter()
+        .map(|line| match line.strip_prefix("RRULE:") {
+            Some(rule) => {
+                let mut parts: Vec<&str> = rule
+                    .split(';')
+                    .filter(|part| !part.starts_with("UNTIL=") && !part.starts_with("COUNT="))
+                    .collect();
+                let until_part = format!("UNTIL={}", until);
+                parts.push(&until_part);
+
+                format!("RRULE:{}", parts.join(";"))
+            }
+
+            None => line.clone(),
+        })
+        .collect()
+}
+
+// Google names each instance of a recurring event after the event and the
+// instance's original start in UTC
+pub fn recurring_instance_id(event_id: &str, start_time: OffsetDateTime) -> String {
+    let utc_format =
+        time::macros::format_description!("[year][month][day]T[hour][minute][second]Z");
+
+    format!(
+        "{}_{}",
+        event_id,
+        start_time
+            .to_offset(time::UtcOffset::UTC)
+            .format(&utc_format)
+            .unwrap_or_default()
+    )
+}
+
+// Windows of one override date, a close before the open runs past midnight
+pub fn override_windows_overlap(windows: &[(Time, Time)]) -> bool {
+    let mut spans: Vec<(Duration, Duration)> = windows
+        .iter()
+        .map(|(open, close)| {
+            let open = *open - Time::MIDNIGHT;
+            let close = *close - Time::MIDNIGHT;
+
+            (
+                open,
+                if close > open {
+                    close
+                } else {
+                    close + Duration::days(1)
+                },
+            )
+        })
+        .collect();
+
+    spans.sort();
+    spans.windows(2).any(|pair| pair[1].0 < pair[0].1)
+}
+
+// Business-local dates of the given instants, the slot cache is keyed on those
+pub fn local_dates(starts: &[OffsetDateTime], time_zone: &str) -> Vec<Date> {
+    let mut dates: Vec<Date> = starts
+        .iter()
+        .filter_map(|start| convert_to_local_primitive(*start, time_zone).ok())
+        .map(|local_start| local_start.date())
+        .collect();
+
+    dates.sort();
+    dates.dedup();
+    dates
+}
+
+// Openings still ahead with their local date, the waitlist is matched on
+// those, and how many seats each of them freed
+pub fn waitlist_openings(
+    freed_starts: &[OffsetDateTime],
+    now: OffsetDateTime,
+    time_zone: &str,
+) -> Vec<(OffsetDateTime, Date, usize)> {
+    let mut openings: Vec<(OffsetDateTime, Date, usize)> = Vec::new();
+
+    for start in freed_starts.iter().filter(|start| **start > now) {
+        if let Some(opening) = openings.iter_mut().find(|(seen, _, _)| seen == start) {
+            opening.2 += 1;
+            continue;
+        }
+
+        if let Ok(local_start) = convert_to_local_primitive(*start, time_zone) {
+            openings.push((*start, local_start.date(), 1));
+        }
+    }
+
+    openings
+}
+
+// Who hears about an opening: one entry per freed seat, in the order they
+// joined. Entries told earlier stay in line, unless already told this time.
+pub fn waitlist_recipients<'a>(
+    entries: &'a [WaitlistEntry],
+    local_date: Date,
+    seats: usize,
+    notified: &HashSet<Uuid>,
+) -> Vec<&'a WaitlistEntry> {
+    let mut eligible: Vec<&WaitlistEntry> = entries
+        .iter()
+        .filter(|entry| (entry.preferred_from..=entry.preferred_to).contains(&local_date))
+        .filter(|entry| !notified.contains(&entry.id))
+        .collect();
+
+    eligible.sort_by_key(|entry| (entry.created_at, entry.id));
+    eligible.truncate(seats);
+
+    eligible
+}
@@ -0,0 +1,110 @@
+use crate::{
+    structs::{db_struct::WaitlistEntry, util_struct::WaitlistNotification},
+    utils::{
+        notifier_utils::Notifier,
+        others_utils::{waitlist_openings, waitlist_recipients},
+    },
+};
+use sqlx::PgPool;
+use std::collections::HashSet;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+// Tells the waitlist about slots of a service that just opened up. As many
+// entries as seats were freed hear about each opening, in the order they
+// joined. Runs after the change is committed, so failures are only logged.
+pub async fn notify_waitlist(
+    pool: &PgPool,
+    notifier: &dyn Notifier,
+    business_id: Uuid,
+    service_id: Uuid,
+    freed_starts: &[OffsetDateTime],
+) {
+    if let Err(e) = notify_openings(pool, notifier, business_id, service_id, freed_starts).await {
+        eprintln!("Failed to notify the waitlist: {}", e);
+    }
+}
+
+async fn notify_openings(
+    pool: &PgPool,
+    notifier: &dyn Notifier,
+    business_id: Uuid,
+    service_id: Uuid,
+    freed_starts: &[OffsetDateTime],
+) -> Result<(), String> {
+    let business = sqlx::query!(
+        r#"
+        SELECT u.time_zone, s.service_name
+        FROM users u
+        JOIN services s ON s.user_id = u.id
+        WHERE u.id = $1 AND s.id = $2
+        "#,
+        business_id,
+        service_id
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let openings = waitlist_openings(freed_starts, OffsetDateTime::now_utc(), &business.time_zone);
+
+    let (Some(first_date), Some(last_date)) = (
+        openings.iter().map(|(_, local_date, _)| *local_date).min(),
+        openings.iter().map(|(_, local_date, _)| *local_date).max(),
+    ) else {
+        return Ok(());
+    };
+
+    let entries = sqlx::query_as!(
+        WaitlistEntry,
+        r#"
+        SELECT * FROM waitlist_entries
+        WHERE business_id = $1
+        AND service_id = $2
+        AND preferred_from <= $4
+        AND preferred_to >= $3
+        "#,
+        business_id,
+        service_id,
+        first_date,
+        last_date
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut notified = HashSet::new();
+
+    for (slot_start_time, local_date, seats) in openings {
+        for entry in waitlist_recipients(&entries, local_date, seats, &notified) {
+            let notification = WaitlistNotification {
+                waitlist_entry_id: entry.id,
+                business_id,
+                service_id,
+                service_name: business.service_name.clone(),
+                customer_name: entry.customer_name.clone(),
+                customer_email: entry.customer_email.clone(),
+                customer_phone: entry.customer_phone.clone(),
+                slot_start_time,
+            };
+
+            // One unreachable customer does not hold up the rest of the line
+            if let Err(e) = notifier.notify(&notification).await {
+                eprintln!("Failed to notify waitlist entry {}: {}", entry.id, e);
+                continue;
+            }
+
+            notified.insert(entry.id);
+
+            sqlx::query!(
+                "UPDATE waitlist_entries SET notified_at = NOW() WHERE id = $1",
+                entry.id
+            )
+            .execute(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        }
+    }
+
+    Ok(())
+}